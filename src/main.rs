@@ -1,18 +1,30 @@
+mod categories;
+
+use chrono::NaiveDate;
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode},
+    event::{
+        self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent, KeyModifiers,
+        MouseButton, MouseEventKind,
+    },
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
+use log::{debug, error, info, warn};
 use std::io::prelude::*;
+use std::path::PathBuf;
 use std::process::Command;
-use std::{collections::HashSet, fs::File};
-use std::{error::Error, io};
+use std::time::{Duration, Instant};
+use std::{
+    collections::{HashMap, HashSet},
+    fs::File,
+};
+use std::{error::Error, fmt, io};
 use tui::{
     backend::{Backend, CrosstermBackend},
-    layout::{Alignment, Constraint, Layout},
+    layout::{Alignment, Constraint, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Span, Spans, Text},
-    widgets::{Block, Borders, Cell, Paragraph, Row, Table, TableState, Wrap},
+    widgets::{Block, Borders, Cell, Clear, Paragraph, Row, Table, TableState, Wrap},
     Frame, Terminal,
 };
 
@@ -52,431 +64,5192 @@ pub struct Category {
 }
 
 const BASE_URL: &str = "https://arxiv-json-api.fly.dev";
-const FILE_PATH: &str = ".arxiv-cli";
+const FOOTER_HINTS: &str = "/ search  n/p page  s save  o open  Enter details  h help  q quit";
+const NOTHING_SELECTED: &str = "nothing selected";
+const ROW_HEIGHT: u16 = 8;
+const TITLE_PERCENT: u16 = 34;
+const SUMMARY_PERCENT: u16 = 32;
+const MAX_AUTHORS: usize = 3;
+const SEEN_MARKER: &str = "✅";
+const UNSEEN_MARKER: &str = "❌";
+// a typical silent reading speed for technical prose
+const WORDS_PER_MINUTE: usize = 200;
+const DETAILED_ROW_HEIGHT: u16 = 14;
+const DETAILED_MAX_AUTHORS: usize = 10;
+const MAX_ROW_HEIGHT: u16 = 20;
+// the table header is one line plus a one line bottom margin
+const HEADER_HEIGHT: u16 = 2;
+// below this the columns are too narrow to read
+const MIN_WIDTH: u16 = 60;
+const CANCEL_POLL: Duration = Duration::from_millis(50);
+const DOUBLE_CLICK: Duration = Duration::from_millis(400);
+const RETRY_DELAYS: [Duration; 3] = [
+    Duration::from_millis(200),
+    Duration::from_millis(400),
+    Duration::from_millis(800),
+];
+// cap how long a Retry-After header can freeze the ui for
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(10);
+// rate limiting waits in the background, so it can hold off for longer
+const MAX_RATE_LIMIT_WAIT: Duration = Duration::from_secs(120);
+const USER_AGENT: &str = concat!("arxiv-cli/", env!("CARGO_PKG_VERSION"));
+const NO_COLOR_VAR: &str = "NO_COLOR";
+const TIMEOUT_VAR: &str = "ARXIV_CLI_TIMEOUT";
+const PROXY_VAR: &str = "ARXIV_CLI_PROXY";
+const LOG_VAR: &str = "ARXIV_CLI_LOG";
+// the usual proxy variables, most specific first. NO_PROXY is read by reqwest.
+const PROXY_VARS: &[(&str, &str)] = &[
+    ("https", "HTTPS_PROXY"),
+    ("https", "https_proxy"),
+    ("http", "HTTP_PROXY"),
+    ("http", "http_proxy"),
+    ("all", "ALL_PROXY"),
+    ("all", "all_proxy"),
+];
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+const API_URL_VAR: &str = "ARXIV_CLI_API_URL";
+const DATA_DIR_VAR: &str = "ARXIV_CLI_DATA_DIR";
+const DATA_DIR: &str = "arxiv-cli";
+const FALLBACK_DATA_DIR: &str = ".arxiv-cli";
+const BOOKMARKS_FILE: &str = "bookmarks.json";
+const DEFAULT_COLLECTION: &str = "default";
+const COLLECTIONS_DIR: &str = "collections";
+const NOTES_FILE: &str = "notes.json";
+const READ_FILE: &str = "read.json";
+const CITATIONS_FILE: &str = "citations.bib";
+const SESSION_FILE: &str = "session.json";
+// the version that last ran, to tell when there's something new to show
+const VERSION_FILE: &str = "last_version";
+// shown once after upgrading, so keep it to what's worth knowing about
+const WHATS_NEW: &[&str] = &[
+    "Ctrl-D/Ctrl-U move by half a screen, Ctrl-F/Ctrl-B by a whole one",
+    "/ in the bookmarks view (B) filters by title, author or note",
+    "E opens a paper's LaTeX source, W picks any of its links",
+    "C browses by category, F changes which field searches look in",
+    "v switches between compact, normal and detailed rows",
+    "R refreshes the page and says how many papers are new",
+    "rate limiting from the server now waits it out with a countdown",
+];
+// the first arXiv submissions, used when only an end date is given
+const ARXIV_EPOCH: NaiveDate = match NaiveDate::from_ymd_opt(1991, 8, 1) {
+    Some(date) => date,
+    None => panic!("valid date"),
+};
+const DEFAULT_QUERY: &str = "algorithms";
+const MAX_PAGE: u16 = 1000;
+const MAX_PAGE_SIZE: usize = 100;
+const MAX_QUERY_LENGTH: usize = 300;
+const MAX_HISTORY: usize = 100;
+const CACHE_DIR: &str = "arxiv-cli";
+const RESPONSE_LOG: &str = "last-bad-response.txt";
+const CACHE_TTL_VAR: &str = "ARXIV_CLI_CACHE_TTL";
+const DEFAULT_CACHE_TTL: Duration = Duration::from_secs(60 * 60);
+// expired pages still stand in while offline, so they're only pruned after this
+const MAX_CACHE_AGE: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+const CONFIG_PATH: &str = "arxiv-cli/config.json";
+const DOWNLOAD_DIR_VAR: &str = "ARXIV_CLI_DOWNLOAD_DIR";
+// opening or downloading more than this many pdfs at once asks first
+const BULK_CONFIRM: usize = 10;
+
+// `viewer` is the configured viewer_command, where `{url}` or `{file}` stand in
+// for the target (appended when neither is present). without one the
+// platform's default opener is used.
+fn open_url(viewer: Option<&str>, url: &str) -> io::Result<()> {
+    if let Some(viewer) = viewer {
+        let mut words = viewer.split_whitespace();
+        let program = words
+            .next()
+            .ok_or_else(|| io::Error::other("viewer_command is empty"))?;
+        let mut args: Vec<String> = words
+            .map(|word| word.replace("{url}", url).replace("{file}", url))
+            .collect();
+        if !viewer.contains("{url}") && !viewer.contains("{file}") {
+            args.push(url.to_string());
+        }
+        // viewers usually stay open, so don't wait on them
+        Command::new(program)
+            .args(args)
+            .stdin(std::process::Stdio::null())
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .spawn()
+            .map_err(|e| io::Error::other(format!("failed to run {}: {}", program, e)))?;
+        return Ok(());
+    }
+
+    let mut command = if cfg!(target_os = "macos") {
+        Command::new("open")
+    } else if cfg!(target_os = "windows") {
+        let mut command = Command::new("cmd");
+        // the empty string is the window title `start` expects before the target
+        command.args(["/C", "start", ""]);
+        command
+    } else {
+        Command::new("xdg-open")
+    };
 
-fn open_url(url: &str) {
-    Command::new("xdg-open")
-        .arg(url)
-        .output()
-        .expect("failed to execute process");
+    let output = command.arg(url).output()?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(io::Error::other(format!(
+            "failed to open {}: {}",
+            url,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )))
+    }
 }
 
-#[derive(Clone, Debug)]
-struct Params {
-    page: u16,
-    query: String,
+// "http://arxiv.org/abs/2101.00001v2" -> "2101.00001v2"
+fn arxiv_id(id: &str) -> &str {
+    id.split("/abs/").nth(1).unwrap_or(id)
 }
 
-impl Params {
-    pub fn new() -> Self {
-        Self {
-            page: 1,
-            query: "algorithms".to_string(),
+// "2101.00001v2" -> "2101.00001"
+fn strip_version(id: &str) -> &str {
+    match id.rsplit_once('v') {
+        Some((base, version))
+            if !base.is_empty()
+                && !version.is_empty()
+                && version.chars().all(|c| c.is_ascii_digit()) =>
+        {
+            base
         }
+        _ => id,
     }
+}
 
-    pub fn next_page_by(&mut self, amount: u16) {
-        let page = self.page;
-        self.page = if page + amount < 1000 {
-            page + amount
-        } else {
-            1000
+fn bibtex(item: &Response) -> String {
+    let eprint = strip_version(arxiv_id(&item.id));
+    let authors: Vec<_> = item
+        .authors
+        .iter()
+        .flatten()
+        .map(|x| x.to_string())
+        .collect();
+    let year: String = item.published.chars().take(4).collect();
+
+    let key = match authors
+        .first()
+        .and_then(|author| author.split_whitespace().last())
+    {
+        Some(surname) => {
+            let surname: String = surname.chars().filter(|c| c.is_alphanumeric()).collect();
+            format!("{}{}", surname.to_lowercase(), year)
         }
+        None => eprint.to_string(),
+    };
+
+    format!(
+        "@misc{{{},\n  title = {{{}}},\n  author = {{{}}},\n  year = {{{}}},\n  eprint = {{{}}},\n  archivePrefix = {{arXiv}},\n}}\n",
+        key,
+        item.title.split_whitespace().collect::<Vec<_>>().join(" "),
+        authors.join(" and "),
+        year,
+        eprint
+    )
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum CitationStyle {
+    Plain,
+    Markdown,
+}
+
+// Author et al., "Title", arXiv:ID, Year -- for pasting into prose
+fn citation(item: &Response, style: CitationStyle) -> String {
+    let authors: Vec<_> = item.authors.iter().flatten().collect();
+    let authors = match authors.as_slice() {
+        [] => "Unknown".to_string(),
+        [author] => author.to_string(),
+        [first, second] => format!("{} and {}", first, second),
+        [first, ..] => format!("{} et al.", first),
+    };
+    let title = item.title.split_whitespace().collect::<Vec<_>>().join(" ");
+    let eprint = strip_version(arxiv_id(&item.id));
+    let year: String = item.published.chars().take(4).collect();
+
+    match style {
+        CitationStyle::Plain => format!("{}, \"{}\", arXiv:{}, {}", authors, title, eprint, year),
+        CitationStyle::Markdown => format!(
+            "{}, [\"{}\"](https://arxiv.org/abs/{}), arXiv:{}, {}",
+            authors, title, eprint, eprint, year
+        ),
     }
+}
 
-    pub fn prev_page_by(&mut self, amount: u16) {
-        let page = self.page;
-        self.page = if page <= amount { 0 } else { page - amount }
+// $ARXIV_CLI_DATA_DIR, or arxiv-cli under $XDG_DATA_HOME (or the platform's equivalent).
+// without a home directory, falls back to .arxiv-cli in the current directory.
+fn data_dir() -> Option<PathBuf> {
+    match std::env::var_os(DATA_DIR_VAR) {
+        Some(dir) if !dir.is_empty() => Some(PathBuf::from(dir)),
+        _ => dirs::data_dir()
+            .map(|data| data.join(DATA_DIR))
+            .or_else(fallback_data_dir),
     }
+}
 
-    pub fn set_query<S: Into<String> + std::fmt::Display>(&mut self, query: S) {
-        self.query = query.to_string();
+fn fallback_data_dir() -> Option<PathBuf> {
+    std::env::current_dir()
+        .ok()
+        .map(|dir| dir.join(FALLBACK_DATA_DIR))
+}
+
+// a warning for when bookmarks end up somewhere unexpected, or can't be saved at all
+fn data_dir_warning() -> Option<String> {
+    if std::env::var_os(DATA_DIR_VAR).is_some_and(|dir| !dir.is_empty())
+        || dirs::data_dir().is_some()
+    {
+        return None;
     }
+    Some(match fallback_data_dir() {
+        Some(dir) => format!(
+            "no home directory, so bookmarks are saved in {} (set {} to change this)",
+            dir.display(),
+            DATA_DIR_VAR
+        ),
+        None => format!(
+            "nowhere to save bookmarks: set {} to a directory",
+            DATA_DIR_VAR
+        ),
+    })
 }
 
-#[derive(Clone)]
-struct App {
-    state: TableState,
-    items: Responses,
-    current: Option<usize>,
-    ids: HashSet<String>,
+fn data_file(name: &str) -> Option<PathBuf> {
+    data_dir().map(|dir| dir.join(name))
 }
 
-async fn get_ids() -> HashSet<String> {
-    let read_arxiv_ids = dirs::cache_dir();
-    if let Some(arxiv_ids) = read_arxiv_ids {
-        if let Ok(id) = std::fs::read_to_string(&format!("{}/{}", arxiv_ids.display(), FILE_PATH)) {
-            let mut ids = HashSet::default();
-            for url in id.lines() {
-                ids.insert(url.to_string());
-            }
-            ids
-        } else {
-            HashSet::default()
-        }
-    } else {
-        HashSet::default()
+// where everything lived before it moved into the data dir
+fn legacy_files() -> Vec<(Option<PathBuf>, &'static str)> {
+    let cache = dirs::cache_dir();
+    let home = dirs::home_dir();
+    vec![
+        (cache.as_ref().map(|c| c.join(".arxiv-cli")), BOOKMARKS_FILE),
+        (
+            cache.as_ref().map(|c| c.join(".arxiv-cli-notes")),
+            NOTES_FILE,
+        ),
+        (cache.as_ref().map(|c| c.join(".arxiv-cli-read")), READ_FILE),
+        (
+            cache
+                .as_ref()
+                .map(|c| c.join(CACHE_DIR).join(COLLECTIONS_DIR)),
+            COLLECTIONS_DIR,
+        ),
+        (
+            home.as_ref().map(|h| h.join(".arxiv-cli-session")),
+            SESSION_FILE,
+        ),
+        (
+            home.as_ref().map(|h| h.join(".arxiv-cli-citations.bib")),
+            CITATIONS_FILE,
+        ),
+    ]
+}
+
+// true the first time a new version runs. fresh installs have nothing to compare
+// against, so they only count when there's already a session from an older version.
+fn is_new_version() -> bool {
+    let Some(path) = data_file(VERSION_FILE) else {
+        return false;
+    };
+    let current = env!("CARGO_PKG_VERSION");
+    let new = match std::fs::read_to_string(&path) {
+        Ok(last) => last.trim() != current,
+        Err(_) => data_file(SESSION_FILE).is_some_and(|session| session.exists()),
+    };
+    // remembered straight away so it's shown once however the session ends
+    let remembered = path
+        .parent()
+        .map_or(Ok(()), std::fs::create_dir_all)
+        .and_then(|_| std::fs::write(&path, current));
+    if let Err(e) = remembered {
+        warn!("couldn't save the version to {}: {}", path.display(), e);
     }
+    new
 }
 
-impl App {
-    fn new() -> App {
-        App {
-            state: TableState::default(),
-            items: vec![],
-            current: None,
-            ids: HashSet::new(),
+// moves files from their old locations the first time the data dir is used
+fn migrate_legacy_files() -> io::Result<()> {
+    let Some(dir) = data_dir() else {
+        return Ok(());
+    };
+    for (old, name) in legacy_files() {
+        let new = dir.join(name);
+        match old {
+            Some(old) if old.exists() && !new.exists() => {
+                std::fs::create_dir_all(&dir)?;
+                // a rename can't cross filesystems, so fall back to copying files
+                if std::fs::rename(&old, &new).is_err() && old.is_file() {
+                    std::fs::copy(&old, &new)?;
+                    std::fs::remove_file(&old)?;
+                }
+            }
+            _ => {}
         }
     }
+    Ok(())
+}
 
-    pub fn save_ids(&self) -> std::io::Result<()> {
-        let mut s = String::from("");
-        let read_arxiv_ids = dirs::cache_dir();
-        if let Some(arxiv_ids) = read_arxiv_ids {
-            let mut arxiv_file = File::options()
-                .create(true)
-                .write(true)
-                .truncate(true)
-                .open(&format!("{}/{}", arxiv_ids.display(), FILE_PATH))?;
-            for id in self.ids.iter() {
-                s.push_str(&format!("{}\n", id));
-            }
-
-            write!(arxiv_file, "{}", s)?;
-        };
-        Ok(())
+fn save_citation(item: &Response) -> io::Result<String> {
+    let path = data_file(CITATIONS_FILE)
+        .ok_or_else(|| io::Error::other("could not find the data directory"))?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
     }
+    let mut file = File::options().create(true).append(true).open(&path)?;
+    writeln!(file, "{}", bibtex(item))?;
+    Ok(path.display().to_string())
+}
 
-    pub fn set_ids(&mut self, ids: HashSet<String>) {
-        self.ids = ids;
-    }
+fn pdf_link(item: &Response) -> Option<&Link> {
+    item.links
+        .iter()
+        .find(|link| link.title == Some("pdf".to_string()))
+}
 
-    pub fn add_id(&mut self, id: String) {
-        self.ids.insert(id);
+fn abstract_url(item: &Response) -> &str {
+    item.links
+        .iter()
+        .find(|link| link.rel == *"alternate")
+        .map(|link| link.href.as_str())
+        .unwrap_or(&item.id)
+}
+
+// the ar5iv rendering of the paper, built from the id in its abstract page link
+fn ar5iv_url(item: &Response) -> Option<String> {
+    let link = item.links.iter().find(|link| link.rel == *"alternate")?;
+    parse_arxiv_id(&link.href).map(|id| format!("https://ar5iv.org/abs/{}", id))
+}
+
+// the LaTeX source tarball. the backend doesn't usually list one, but every
+// arXiv paper's source (when the authors haven't withheld it) lives at /e-print/
+fn source_url(item: &Response) -> Option<String> {
+    let listed = item.links.iter().find(|link| {
+        link.title.as_deref() == Some("source")
+            || link.href.contains("/e-print/")
+            || link.href.contains("/src/")
+            || link
+                .type_field
+                .as_deref()
+                .is_some_and(|kind| kind.contains("tar") || kind.contains("eprint"))
+    });
+    if let Some(link) = listed {
+        return Some(link.href.clone());
     }
+    let link = item.links.iter().find(|link| link.rel == *"alternate")?;
+    parse_arxiv_id(&link.href).map(|id| format!("https://arxiv.org/e-print/{}", id))
+}
 
-    pub fn remove_id(&mut self, id: String) {
-        self.ids.remove(&id);
+fn doi_link(item: &Response) -> Option<&Link> {
+    item.links
+        .iter()
+        .find(|link| link.title == Some("doi".to_string()))
+}
+
+// every link the backend gave for a paper as [name, type, url], plus its ar5iv page
+fn all_links(item: &Response) -> Vec<Vec<String>> {
+    let mut links: Vec<_> = item
+        .links
+        .iter()
+        .map(|link| {
+            let name = match (&link.title, link.rel.as_str()) {
+                (Some(title), _) => title.clone(),
+                (None, "alternate") => "abstract".to_string(),
+                (None, rel) => rel.to_string(),
+            };
+            let kind = link.type_field.clone().unwrap_or_default();
+            vec![name, kind, link.href.clone()]
+        })
+        .collect();
+    if let Some(url) = ar5iv_url(item) {
+        links.push(vec![
+            "html (ar5iv)".to_string(),
+            "text/html".to_string(),
+            url,
+        ]);
+    }
+    // unless it was one of the backend's links already
+    if let Some(url) = source_url(item).filter(|url| !item.links.iter().any(|l| l.href == *url)) {
+        links.push(vec![
+            "source".to_string(),
+            "application/x-tar".to_string(),
+            url,
+        ]);
     }
+    links
+}
 
-    pub fn update_items(&mut self, items: Responses) {
-        self.items = items;
+// "152 words, about 1 min read"
+fn reading_time(text: &str) -> String {
+    let words = text.split_whitespace().count();
+    let minutes = words.div_ceil(WORDS_PER_MINUTE).max(1);
+    format!("{} words, about {} min read", words, minutes)
+}
+
+// "http://dx.doi.org/10.1103/PhysRevD.1.1" -> "10.1103/PhysRevD.1.1"
+fn doi(item: &Response) -> Option<&str> {
+    let href = &doi_link(item)?.href;
+    Some(
+        href.split_once("doi.org/")
+            .map_or(href.as_str(), |(_, doi)| doi),
+    )
+}
+
+// which of the places a paper can be opened actually exist for it
+fn available_links(item: &Response) -> Vec<&'static str> {
+    let has_abstract = item.links.iter().any(|link| link.rel == *"alternate");
+    [
+        ("pdf", pdf_link(item).is_some()),
+        ("abstract", has_abstract),
+        ("html", ar5iv_url(item).is_some()),
+        ("doi", doi_link(item).is_some()),
+        ("source", source_url(item).is_some()),
+    ]
+    .into_iter()
+    .filter_map(|(name, available)| available.then_some(name))
+    .collect()
+}
+
+fn download_dir() -> Option<PathBuf> {
+    match std::env::var_os(DOWNLOAD_DIR_VAR) {
+        Some(dir) => Some(PathBuf::from(dir)),
+        None => {
+            dirs::download_dir().or_else(|| dirs::home_dir().map(|home| home.join("Downloads")))
+        }
     }
+}
 
-    pub fn first_item(&mut self) {
-        self.current = Some(0);
-        self.state.select(Some(0))
+// "Attention Is All You Need" + "1706.03762v5" -> "Attention_Is_All_You_Need-1706.03762v5.pdf"
+fn pdf_file_name(item: &Response) -> String {
+    let title: Vec<String> = item
+        .title
+        .split_whitespace()
+        .map(|word| {
+            word.chars()
+                .filter(|c| c.is_alphanumeric() || *c == '-')
+                .collect()
+        })
+        .filter(|word: &String| !word.is_empty())
+        .collect();
+    let id = arxiv_id(&item.id).replace('/', "_");
+    format!("{}-{}.pdf", title.join("_"), id)
+}
+
+async fn download_pdf(
+    client: &reqwest::Client,
+    item: &Response,
+) -> Result<PathBuf, Box<dyn Error>> {
+    let link = pdf_link(item).ok_or("this paper has no pdf link")?;
+    let dir = download_dir().ok_or("could not find a download directory")?;
+
+    // pdfs can take much longer than a page of results
+    let response = client
+        .get(&link.href)
+        .timeout(DOWNLOAD_TIMEOUT)
+        .send()
+        .await?
+        .error_for_status()?;
+    let bytes = response.bytes().await?;
+
+    std::fs::create_dir_all(&dir)?;
+    let path = dir.join(pdf_file_name(item));
+    std::fs::write(&path, bytes)?;
+    Ok(path)
+}
+
+// appends log lines to a file, since stderr is hidden behind the tui
+struct FileLogger {
+    file: std::sync::Mutex<std::fs::File>,
+}
+
+impl log::Log for FileLogger {
+    // everything of ours, but only the noteworthy parts of reqwest and hyper
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.target().starts_with(env!("CARGO_CRATE_NAME"))
+            || metadata.level() <= log::Level::Info
     }
 
-    pub fn last_item(&mut self) {
-        let last = if self.items.is_empty() {
-            Some(0)
-        } else {
-            Some(self.items.len() - 1)
-        };
-        self.current = last;
-        self.state.select(last);
+    fn log(&self, record: &log::Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        if let Ok(mut file) = self.file.lock() {
+            let _ = writeln!(
+                file,
+                "{} {:5} {}: {}",
+                chrono::Local::now().format("%Y-%m-%d %H:%M:%S%.3f"),
+                record.level(),
+                record.target(),
+                record.args()
+            );
+        }
     }
 
-    pub fn next_by(&mut self, amount: usize) {
-        let i = match self.state.selected() {
-            Some(i) => {
-                if i + amount >= self.items.len() - 1 {
-                    self.items.len() - 1
-                } else {
-                    i + amount
-                }
-            }
-            None => 0,
-        };
-        self.current = Some(i);
-        self.state.select(Some(i));
+    fn flush(&self) {
+        if let Ok(mut file) = self.file.lock() {
+            let _ = file.flush();
+        }
     }
+}
 
-    pub fn previous_by(&mut self, amount: usize) {
-        let i = match self.state.selected() {
-            Some(i) => match i {
-                0 => 0,
-                i => {
-                    if amount >= i {
-                        0
-                    } else {
-                        i - amount
-                    }
-                }
-            },
-            None => 0,
-        };
-        self.current = Some(i);
-        self.state.select(Some(i));
+// off unless --log-file or $ARXIV_CLI_LOG names a file
+fn init_logging(path: Option<PathBuf>) -> Result<(), Box<dyn Error>> {
+    let Some(path) = path.or_else(|| std::env::var_os(LOG_VAR).map(PathBuf::from)) else {
+        return Ok(());
+    };
+    let file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|e| format!("couldn't open log file {}: {}", path.display(), e))?;
+    log::set_boxed_logger(Box::new(FileLogger {
+        file: std::sync::Mutex::new(file),
+    }))?;
+    log::set_max_level(log::LevelFilter::Debug);
+    Ok(())
+}
+
+// $ARXIV_CLI_API_URL lets people point at a self-hosted copy of the json proxy
+fn base_url() -> Result<reqwest::Url, Box<dyn Error>> {
+    match std::env::var(API_URL_VAR) {
+        Ok(url) => reqwest::Url::parse(&url)
+            .map_err(|e| format!("{} is not a valid url ({}): {}", API_URL_VAR, url, e).into()),
+        Err(_) => Ok(reqwest::Url::parse(BASE_URL)?),
     }
 }
 
-#[tokio::main]
-async fn main() -> Result<(), Box<dyn Error>> {
-    let mut app = App::new();
-    let mut params = Params::new();
-    let items = get_items(&params);
-    app.update_items(items.await?);
-    app.set_ids(get_ids().await);
+// friendly prefix -> arXiv search field
+const SEARCH_FIELDS: &[(&str, &str)] = &[
+    ("author:", "au"),
+    ("title:", "ti"),
+    ("cat:", "cat"),
+    ("abstract:", "abs"),
+];
 
-    // setup terminal
-    enable_raw_mode()?;
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
-    let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend)?;
+// which field unprefixed search terms are matched against
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Scope {
+    #[default]
+    All,
+    Title,
+    Abstract,
+    Author,
+}
 
-    // create app and run it
-    run_app(&mut terminal, app, &mut params).await?;
+impl Scope {
+    pub fn next(self) -> Self {
+        match self {
+            Scope::All => Scope::Title,
+            Scope::Title => Scope::Abstract,
+            Scope::Abstract => Scope::Author,
+            Scope::Author => Scope::All,
+        }
+    }
 
-    // restore terminal
-    disable_raw_mode()?;
-    execute!(
-        terminal.backend_mut(),
-        LeaveAlternateScreen,
-        DisableMouseCapture
-    )?;
-    terminal.show_cursor()?;
+    pub fn label(self) -> &'static str {
+        match self {
+            Scope::All => "all fields",
+            Scope::Title => "titles",
+            Scope::Abstract => "abstracts",
+            Scope::Author => "authors",
+        }
+    }
 
-    Ok(())
+    fn field(self) -> &'static str {
+        match self {
+            Scope::All => "all",
+            Scope::Title => "ti",
+            Scope::Abstract => "abs",
+            Scope::Author => "au",
+        }
+    }
 }
 
-// fetch the request
-async fn get_items(params: &Params) -> Result<Responses, Box<dyn Error>> {
-    let client = reqwest::Client::new();
+// turns "author:hinton title:transformer" into the arXiv field query
+// "au:hinton AND ti:transformer", with unprefixed terms searched in `scope`.
+// AND/OR/ANDNOT and parentheses are passed through, so only two terms next to
+// each other get an AND between them. plain queries over all fields are sent
+// untouched unless there's a date range to add.
+fn search_query(query: &str, scope: Scope, dates: Option<&str>) -> String {
+    let terms = search_terms(query);
+    let field = |term: &str| {
+        SEARCH_FIELDS.iter().find_map(|(prefix, field)| {
+            term.strip_prefix(prefix)
+                .filter(|value| !value.is_empty())
+                .map(|value| format!("{}:{}", field, value))
+        })
+    };
 
-    let Params { query, page } = params;
+    if scope == Scope::All && dates.is_none() && !terms.iter().any(|term| field(term).is_some()) {
+        return query.to_string();
+    }
 
-    let query = client
-        .get(BASE_URL)
-        .query(&[("q", &query.to_string()), ("p", &page.to_string())]);
-    let res = query.send().await?.json::<Responses>().await?;
+    let mut joined = String::new();
+    let mut has_operator = false;
+    // the last token ended an operand, so a term or "(" after it needs an AND
+    let mut after_operand = false;
+    for term in terms {
+        let operator = is_operator(term);
+        if !joined.is_empty() {
+            let starts_operand = !operator && term != ")";
+            joined.push_str(if after_operand && starts_operand {
+                " AND "
+            } else {
+                " "
+            });
+        }
+        match term {
+            _ if operator => joined.push_str(term),
+            "(" | ")" => joined.push_str(term),
+            _ => joined
+                .push_str(&field(term).unwrap_or_else(|| format!("{}:{}", scope.field(), term))),
+        }
+        has_operator |= operator;
+        after_operand = !operator && term != "(";
+    }
 
-    Ok(res)
+    match dates {
+        // keep an OR from taking the date window with it
+        Some(dates) if has_operator => format!("({}) AND {}", joined, dates),
+        Some(dates) if !joined.is_empty() => format!("{} AND {}", joined, dates),
+        Some(dates) => dates.to_string(),
+        None => joined,
+    }
 }
 
-async fn run_app<B: Backend>(
-    terminal: &mut Terminal<B>,
-    mut app: App,
-    params: &mut Params,
-) -> Result<(), Box<dyn Error>> {
-    let mut amount = String::from("");
-    loop {
-        terminal.draw(|f| ui(f, &mut app))?;
-        if let Event::Key(key) = event::read()? {
-            match key.code {
-                KeyCode::Char('9') => amount.push('9'),
-                KeyCode::Char('8') => amount.push('8'),
-                KeyCode::Char('7') => amount.push('7'),
-                KeyCode::Char('6') => amount.push('6'),
-                KeyCode::Char('5') => amount.push('5'),
-                KeyCode::Char('4') => amount.push('4'),
-                KeyCode::Char('3') => amount.push('3'),
-                KeyCode::Char('2') => amount.push('2'),
-                KeyCode::Char('1') => amount.push('1'),
-                KeyCode::Char('0') => amount.push('0'),
-                KeyCode::Char('q') => {
-                    app.save_ids()?;
-                    return Ok(());
-                }
-                KeyCode::Down | KeyCode::Char('j') => {
-                    app.next_by(amount.parse::<usize>().unwrap_or(1));
-                    amount = String::default();
-                }
-                KeyCode::Up | KeyCode::Char('k') => {
-                    app.previous_by(amount.parse::<usize>().unwrap_or(1));
-                    amount = String::default();
-                }
-                KeyCode::Char('G') => app.last_item(),
-                KeyCode::Char('g') => app.first_item(),
-                KeyCode::Char('n') => {
-                    params.next_page_by(amount.parse::<u16>().unwrap_or(1));
-                    let items = get_items(params).await?;
-                    app.update_items(items);
-                    terminal.draw(|f| ui(f, &mut app))?;
-                }
-                KeyCode::Char('p') => {
-                    params.prev_page_by(amount.parse::<u16>().unwrap_or(1));
-                    let items = get_items(params).await?;
-                    app.update_items(items);
-                    terminal.draw(|f| ui(f, &mut app))?;
-                }
-                KeyCode::Char('/') => {
-                    let mut query = String::from("");
-                    loop {
-                        if let Event::Key(key) = event::read()? {
-                            match key.code {
-                                KeyCode::Char(c) => query.push(c),
-                                KeyCode::Enter => break,
-                                KeyCode::Backspace => {
-                                    query.pop();
-                                }
-                                _ => {}
-                            }
-                        }
-                        terminal.draw(|f| search_ui(f, &query))?;
-                    }
-                    params.set_query(query);
-                    let items = get_items(params).await?;
-                    app.update_items(items);
-                    terminal.draw(|f| ui(f, &mut app))?;
-                }
-                KeyCode::Char('o') => {
-                    let pdf_links = app.items[app.current.unwrap_or(0)]
-                        .links
-                        .iter()
-                        .find(|link| link.title == Some("pdf".to_string()));
-
-                    if let Some(link) = pdf_links {
-                        open_url(&link.href);
-                    }
-                }
-                KeyCode::Char('t') => {
-                    let alternate_link = app.items[app.current.unwrap_or(0)]
-                        .links
-                        .iter()
-                        .find(|link| link.rel == *"alternate");
+fn is_operator(term: &str) -> bool {
+    matches!(term, "AND" | "OR" | "ANDNOT")
+}
 
-                    if let Some(link) = alternate_link {
-                        let html_link = link.href.replace("arxiv", "ar5iv");
-                        open_url(&html_link);
-                    }
-                }
-                KeyCode::Char('b') => {
-                    params.set_query("");
-                    let items = get_items(params).await?;
-                    app.update_items(items);
-                    terminal.draw(|f| ui(f, &mut app))?;
-                }
-                KeyCode::Char('h') => loop {
-                    terminal.draw(|f| popup_ui(f))?;
-                    if let Event::Key(_) = event::read()? {
-                        break;
-                    }
-                },
-                KeyCode::Char('s') => {
-                    let id = &app.items[app.current.unwrap_or(0)].id;
-                    app.add_id(id.to_string());
-                }
-                KeyCode::Char('r') => {
-                    let id = &app.items[app.current.unwrap_or(0)].id;
-                    app.remove_id(id.to_string());
+// splits on whitespace, except inside double quotes so `title:"a b"` stays one
+// term. parentheses are terms of their own.
+fn search_terms(query: &str) -> Vec<&str> {
+    let mut terms = vec![];
+    let mut start = None;
+    let mut quoted = false;
+    for (i, c) in query.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            c if (c.is_whitespace() || c == '(' || c == ')') && !quoted => {
+                if let Some(start) = start.take() {
+                    terms.push(&query[start..i]);
                 }
-                KeyCode::Char('d') => {
-                    let pdf_links = app.items[app.current.unwrap_or(0)]
-                        .links
-                        .iter()
-                        .find(|link| link.title == Some("pdf".to_string()));
-                    let home_dir = dirs::home_dir();
-
-                    if let (Some(link), Some(home)) = (pdf_links, home_dir) {
-                        let home_dir = home.display();
-                        let user_agent = "Mozilla/5.0 (X11; Linux x86_64; rv:104.0) Gecko/20100101 Firefox/104.0";
-                        let client = reqwest::Client::builder().user_agent(user_agent).build()?;
-                        let response = client.get(&link.href).send().await?;
-                        let file_name = response
-                            .url()
-                            .path_segments()
-                            .and_then(|segments| segments.last())
-                            .and_then(|name| if name.is_empty() { None } else { Some(name) })
-                            .unwrap_or("rand.pdf");
-
-                        let file_path = format!(
-                            "{}/{}",
-                            home_dir, "/monorepo/papers/papers/_papers-to-sort/"
-                        );
-
-                        use std::path::Path;
-                        let path = Path::new(&file_path);
-                        let joined_path = Path::join(path, file_name);
-                        let mut file = File::create(joined_path)?;
-
-                        let mut content = std::io::Cursor::new(response.bytes().await?);
-                        std::io::copy(&mut content, &mut file)?;
-                        let id = &app.items[app.current.unwrap_or(0)].id;
-                        app.add_id(id.to_string());
-                    }
+                if !c.is_whitespace() {
+                    terms.push(&query[i..i + 1]);
                 }
-                _ => {}
+                continue;
             }
+            _ => {}
         }
+        start.get_or_insert(i);
+    }
+    if let Some(start) = start {
+        terms.push(&query[start..]);
     }
+    terms
 }
 
-fn search_ui<B: Backend>(f: &mut Frame<B>, text: &str) {
-    let size = f.size();
+// "2023-05-01T17:59:59Z" -> "2023-05-01 17:59 (3 days ago)", or the raw string if it doesn't parse
+fn describe_date(date: &str) -> String {
+    match chrono::DateTime::parse_from_rfc3339(date) {
+        Ok(date) => {
+            let ago = chrono::Utc::now().signed_duration_since(date);
+            format!("{} ({})", date.format("%Y-%m-%d %H:%M"), relative_time(ago))
+        }
+        Err(_) => date.to_string(),
+    }
+}
 
-    let chunks = Layout::default()
-        .constraints([Constraint::Percentage(20)].as_ref())
-        .split(size);
+fn relative_time(ago: chrono::Duration) -> String {
+    let (count, unit) = match ago.num_days() {
+        days if days >= 365 => (days / 365, "year"),
+        days if days >= 30 => (days / 30, "month"),
+        days if days >= 1 => (days, "day"),
+        _ if ago.num_hours() >= 1 => (ago.num_hours(), "hour"),
+        _ => return "just now".to_string(),
+    };
+    let plural = if count == 1 { "" } else { "s" };
+    format!("{} {}{} ago", count, unit, plural)
+}
 
-    let paragraph = Paragraph::new(Span::styled(text, Style::default()))
-        .alignment(Alignment::Center)
-        .wrap(Wrap { trim: true });
-    f.render_widget(paragraph, chunks[0]);
+// a paper has been revised since it was first submitted
+fn is_revised(item: &Response) -> bool {
+    item.updated != item.published
 }
 
-fn ui<B: Backend>(f: &mut Frame<B>, app: &mut App) {
-    let rects = Layout::default()
-        .constraints([Constraint::Percentage(100)].as_ref())
-        .margin(1)
-        .split(f.size());
+// "http://arxiv.org/abs/1706.03762v5" -> Some(5)
+fn version(item: &Response) -> Option<u32> {
+    let id = arxiv_id(&item.id);
+    let (_, version) = id.rsplit_once('v')?;
+    version.parse().ok()
+}
 
-    let selected_style = Style::default().add_modifier(Modifier::REVERSED);
-    let normal_style = Style::default().bg(Color::Blue);
-    let header_cells = ["Seen", "Title", "Summary", "Authors", "Date"]
+// the backend only gives the latest version it knew of, so a revised paper
+// may have moved on again since the page was fetched
+fn describe_version(item: &Response) -> String {
+    match (version(item), is_revised(item)) {
+        (Some(version), true) => format!(
+            "v{} (revised, and newer versions may exist; the abstract page lists them all)",
+            version
+        ),
+        (Some(version), false) => format!("v{}", version),
+        (None, _) => "unknown".to_string(),
+    }
+}
+
+fn parse_date(date: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .map_err(|_| format!("not a date (expected YYYY-MM-DD): {}", date))
+}
+
+// arXiv's submittedDate range, which needs both ends
+fn date_range(after: Option<NaiveDate>, before: Option<NaiveDate>) -> Option<String> {
+    if after.is_none() && before.is_none() {
+        return None;
+    }
+    let after = after.unwrap_or(ARXIV_EPOCH);
+    let before = before.unwrap_or_else(|| chrono::Local::now().date_naive());
+    Some(format!(
+        "submittedDate:[{}0000 TO {}2359]",
+        after.format("%Y%m%d"),
+        before.format("%Y%m%d")
+    ))
+}
+
+// accepts "2301.12345", "arXiv:2301.12345v2", "hep-th/9901001" and abs/pdf urls
+fn parse_arxiv_id(input: &str) -> Option<String> {
+    let input = input.trim();
+    let id = ["/abs/", "/pdf/"]
         .iter()
-        .map(|h| Cell::from(*h).style(Style::default().fg(Color::Red)));
-    let header = Row::new(header_cells)
-        .style(normal_style)
-        .height(1)
-        .bottom_margin(1);
-    let rows = app.items.iter().map(|item| {
-        let Response {
-            id,
-            updated,
-            title,
-            summary,
-            authors,
-            ..
-        } = item;
-        let flattened_authors: Vec<_> = authors.iter().flatten().map(|x| x.to_string()).collect();
-        let authors_str = flattened_authors.join(", ");
-        let height = 8;
+        .find_map(|marker| input.split_once(marker).map(|(_, id)| id))
+        .unwrap_or(input);
+    let id = id.strip_prefix("arXiv:").unwrap_or(id);
+    let id = id.trim_end_matches('/').trim_end_matches(".pdf");
 
-        let viewed = if app.ids.contains(id) { "✅" } else { "❌" };
-        let cells = [viewed, title, summary, &authors_str, updated]
-            .map(|x| Cell::from(Text::from(x.to_string())));
-        Row::new(cells).height(height as u16).bottom_margin(1)
-    });
-    let t = Table::new(rows)
-        .header(header)
-        .block(Block::default().borders(Borders::ALL).title("Table"))
-        .highlight_style(selected_style)
-        .highlight_symbol(">> ")
-        .widths(&[
-            Constraint::Percentage(2),
-            Constraint::Percentage(32),
-            Constraint::Percentage(38),
-            Constraint::Percentage(16),
-            Constraint::Percentage(6),
-        ]);
-    f.render_stateful_widget(t, rects[0], &mut app.state);
+    let valid = !id.is_empty()
+        && id.chars().any(|c| c.is_ascii_digit())
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '/'));
+    if valid {
+        Some(id.to_string())
+    } else {
+        None
+    }
 }
 
-fn popup_ui<B: Backend>(f: &mut Frame<B>) {
-    let size = f.size();
+// commands typed after `:`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Action {
+    Page(u16),
+    Open,
+    Save,
+    Sort(SortMode),
+    Scope(Scope),
+    // None clears that end of the range
+    After(Option<NaiveDate>),
+    Before(Option<NaiveDate>),
+    Quit,
+}
+
+fn parse_action(line: &str) -> Result<Action, String> {
+    let mut words = line.split_whitespace();
+    let command = words.next().unwrap_or("");
+    let arg = words.next();
+
+    match (command, arg) {
+        ("page" | "p", Some(page)) => page
+            .parse()
+            .map(Action::Page)
+            .map_err(|_| format!("not a page number: {}", page)),
+        ("open" | "o", None) => Ok(Action::Open),
+        ("save" | "w", None) => Ok(Action::Save),
+        ("quit" | "q", None) => Ok(Action::Quit),
+        ("after", date) => date.map(parse_date).transpose().map(Action::After),
+        ("before", date) => date.map(parse_date).transpose().map(Action::Before),
+        ("sort", Some(sort)) => match sort {
+            "relevance" => Ok(Action::Sort(SortMode::Relevance)),
+            "date" | "updated" => Ok(Action::Sort(SortMode::Updated)),
+            "published" => Ok(Action::Sort(SortMode::Published)),
+            "title" => Ok(Action::Sort(SortMode::Title)),
+            _ => Err(format!("unknown sort: {}", sort)),
+        },
+        ("scope", Some(scope)) => match scope {
+            "all" => Ok(Action::Scope(Scope::All)),
+            "title" => Ok(Action::Scope(Scope::Title)),
+            "abstract" => Ok(Action::Scope(Scope::Abstract)),
+            "author" => Ok(Action::Scope(Scope::Author)),
+            _ => Err(format!("unknown scope: {}", scope)),
+        },
+        _ => Err(format!("unknown command: {}", line.trim())),
+    }
+}
+
+fn csv_field(field: &str) -> String {
+    format!("\"{}\"", field.replace('"', "\"\""))
+}
+
+fn to_csv(items: &[Response]) -> String {
+    let mut csv = String::from("id,title,authors,date,categories\n");
+    for item in items {
+        let authors: Vec<_> = item
+            .authors
+            .iter()
+            .flatten()
+            .map(|x| x.to_string())
+            .collect();
+        let categories: Vec<_> = item.categories.iter().map(|c| c.term.as_str()).collect();
+        let title = item.title.split_whitespace().collect::<Vec<_>>().join(" ");
+        let row = [
+            item.id.as_str(),
+            &title,
+            &authors.join("; "),
+            &item.updated,
+            &categories.join("; "),
+        ]
+        .map(csv_field)
+        .join(",");
+        csv.push_str(&row);
+        csv.push('\n');
+    }
+    csv
+}
+
+// writes items to a timestamped file in the working directory
+fn export_items(items: &[Response], extension: &str) -> Result<String, Box<dyn Error>> {
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)?
+        .as_secs();
+    let path = format!("arxiv-export-{}.{}", timestamp, extension);
+    let contents = match extension {
+        "csv" => to_csv(items),
+        _ => serde_json::to_string_pretty(items)?,
+    };
+    std::fs::write(&path, contents)?;
+    Ok(path)
+}
+
+// case insensitive subsequence match, so "attn need" matches "Attention Is All You Need"
+fn fuzzy_match(pattern: &str, text: &str) -> bool {
+    let mut text = text.chars().flat_map(char::to_lowercase);
+    pattern
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .all(|p| text.any(|t| t == p))
+}
+
+const LATEX_SYMBOLS: &[(&str, &str)] = &[
+    ("alpha", "α"),
+    ("beta", "β"),
+    ("gamma", "γ"),
+    ("delta", "δ"),
+    ("epsilon", "ε"),
+    ("varepsilon", "ε"),
+    ("zeta", "ζ"),
+    ("eta", "η"),
+    ("theta", "θ"),
+    ("kappa", "κ"),
+    ("lambda", "λ"),
+    ("mu", "μ"),
+    ("nu", "ν"),
+    ("xi", "ξ"),
+    ("pi", "π"),
+    ("rho", "ρ"),
+    ("sigma", "σ"),
+    ("tau", "τ"),
+    ("phi", "φ"),
+    ("varphi", "φ"),
+    ("chi", "χ"),
+    ("psi", "ψ"),
+    ("omega", "ω"),
+    ("Gamma", "Γ"),
+    ("Delta", "Δ"),
+    ("Theta", "Θ"),
+    ("Lambda", "Λ"),
+    ("Sigma", "Σ"),
+    ("Phi", "Φ"),
+    ("Psi", "Ψ"),
+    ("Omega", "Ω"),
+    ("nabla", "∇"),
+    ("partial", "∂"),
+    ("infty", "∞"),
+    ("sum", "∑"),
+    ("prod", "∏"),
+    ("int", "∫"),
+    ("sqrt", "√"),
+    ("cdot", "·"),
+    ("times", "×"),
+    ("pm", "±"),
+    ("leq", "≤"),
+    ("le", "≤"),
+    ("geq", "≥"),
+    ("ge", "≥"),
+    ("neq", "≠"),
+    ("approx", "≈"),
+    ("sim", "∼"),
+    ("in", "∈"),
+    ("subset", "⊂"),
+    ("subseteq", "⊆"),
+    ("cup", "∪"),
+    ("cap", "∩"),
+    ("forall", "∀"),
+    ("exists", "∃"),
+    ("to", "→"),
+    ("rightarrow", "→"),
+    ("leftarrow", "←"),
+    ("mapsto", "↦"),
+    ("ell", "ℓ"),
+    ("log", "log"),
+    ("exp", "exp"),
+    ("ldots", "…"),
+    ("dots", "…"),
+    ("cdots", "⋯"),
+];
+
+// commands whose braces are just dropped, like \emph{word} -> word
+const LATEX_STYLES: &[&str] = &[
+    "emph",
+    "textbf",
+    "textit",
+    "texttt",
+    "textrm",
+    "text",
+    "mathrm",
+    "mathbf",
+    "mathit",
+    "mathcal",
+    "mathbb",
+    "mathsf",
+    "operatorname",
+];
+
+fn superscript(c: char) -> Option<char> {
+    Some(match c {
+        '0' => '⁰',
+        '1' => '¹',
+        '2' => '²',
+        '3' => '³',
+        '4' => '⁴',
+        '5' => '⁵',
+        '6' => '⁶',
+        '7' => '⁷',
+        '8' => '⁸',
+        '9' => '⁹',
+        '+' => '⁺',
+        '-' => '⁻',
+        'n' => 'ⁿ',
+        'i' => 'ⁱ',
+        'T' => 'ᵀ',
+        _ => return None,
+    })
+}
+
+fn subscript(c: char) -> Option<char> {
+    Some(match c {
+        '0' => '₀',
+        '1' => '₁',
+        '2' => '₂',
+        '3' => '₃',
+        '4' => '₄',
+        '5' => '₅',
+        '6' => '₆',
+        '7' => '₇',
+        '8' => '₈',
+        '9' => '₉',
+        '+' => '₊',
+        '-' => '₋',
+        'i' => 'ᵢ',
+        'j' => 'ⱼ',
+        'k' => 'ₖ',
+        'n' => 'ₙ',
+        't' => 'ₜ',
+        _ => return None,
+    })
+}
+
+// the index of the brace closing the one at `open`, if it's closed
+fn brace_group_end(chars: &[char], open: usize) -> Option<usize> {
+    let mut depth = 0;
+    for (i, c) in chars.iter().enumerate().skip(open) {
+        match c {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+// a conservative LaTeX -> unicode pass: known commands, style braces, and
+// super/subscripts inside $...$. anything it doesn't recognise is left exactly
+// as written, including the brace groups after unknown commands.
+fn prettify_latex(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = String::with_capacity(text.len());
+    // whether each open brace belongs to a dropped style command
+    let mut braces: Vec<bool> = vec![];
+    let mut in_math = false;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            '$' if in_math => in_math = false,
+            // "$5 and $10" is money, not math
+            '$' if chars
+                .get(i + 1)
+                .is_some_and(|next| !next.is_ascii_digit() && !next.is_whitespace())
+                && chars[i + 1..].contains(&'$') =>
+            {
+                in_math = true
+            }
+            '\\' => {
+                let name: String = chars[i + 1..]
+                    .iter()
+                    .take_while(|c| c.is_ascii_alphabetic())
+                    .collect();
+                let after = i + 1 + name.chars().count();
+                if let Some((_, symbol)) = LATEX_SYMBOLS.iter().find(|(n, _)| *n == name) {
+                    out.push_str(symbol);
+                    i = after;
+                    continue;
+                }
+                if LATEX_STYLES.contains(&name.as_str()) && chars.get(after) == Some(&'{') {
+                    braces.push(true);
+                    i = after + 1;
+                    continue;
+                }
+                if !name.is_empty() {
+                    // an unknown command, copied along with its arguments
+                    let mut end = after;
+                    while chars.get(end) == Some(&'{') {
+                        match brace_group_end(&chars, end) {
+                            Some(close) => end = close + 1,
+                            None => break,
+                        }
+                    }
+                    out.extend(&chars[i..end]);
+                    i = end;
+                    continue;
+                }
+                match chars.get(i + 1) {
+                    // escaped punctuation like \% or \&
+                    Some(next) if "%&$#_{}".contains(*next) => {
+                        out.push(*next);
+                        i += 2;
+                        continue;
+                    }
+                    _ => out.push(c),
+                }
+            }
+            '^' | '_' if in_math => {
+                let convert = if c == '^' { superscript } else { subscript };
+                let (group, end): (Vec<char>, usize) = if chars.get(i + 1) == Some(&'{') {
+                    match chars[i + 2..].iter().position(|c| *c == '}') {
+                        Some(len) => (chars[i + 2..i + 2 + len].to_vec(), i + 3 + len),
+                        None => (vec![], i + 1),
+                    }
+                } else {
+                    (chars.get(i + 1).copied().into_iter().collect(), i + 2)
+                };
+                let converted: Option<String> = group.iter().map(|c| convert(*c)).collect();
+                match converted {
+                    Some(converted) if !group.is_empty() => {
+                        out.push_str(&converted);
+                        i = end;
+                        continue;
+                    }
+                    _ => out.push(c),
+                }
+            }
+            '{' => {
+                braces.push(false);
+                if !in_math {
+                    out.push(c);
+                }
+            }
+            '}' => match braces.pop() {
+                Some(true) => {}
+                Some(false) if in_math => {}
+                _ => out.push(c),
+            },
+            _ => out.push(c),
+        }
+        i += 1;
+    }
+    out
+}
+
+#[derive(Clone, Debug)]
+struct Params {
+    page: u16,
+    query: String,
+    base_url: reqwest::Url,
+    // submission date window, inclusive
+    after: Option<NaiveDate>,
+    before: Option<NaiveDate>,
+    // applied to search terms without a field prefix
+    scope: Scope,
+    // the backend always sends a full page, so this only trims what's kept
+    page_size: Option<usize>,
+    // how long a cached page is served without asking the backend
+    cache_ttl: Duration,
+    // shared so paging reuses pooled connections
+    client: reqwest::Client,
+    // when a 429 says it's ok to ask again, shared between clones so prefetches wait too
+    rate_limited_until: std::sync::Arc<std::sync::Mutex<Option<Instant>>>,
+}
+
+impl Params {
+    pub fn new() -> Self {
+        Self {
+            page: 1,
+            query: DEFAULT_QUERY.to_string(),
+            base_url: reqwest::Url::parse(BASE_URL).expect("BASE_URL is a valid url"),
+            after: None,
+            before: None,
+            scope: Scope::All,
+            page_size: None,
+            cache_ttl: DEFAULT_CACHE_TTL,
+            client: client(DEFAULT_TIMEOUT, vec![]).expect("the default client can be built"),
+            rate_limited_until: Default::default(),
+        }
+    }
+
+    // how much longer requests have to wait for after being rate limited
+    pub fn rate_limited_for(&self) -> Option<Duration> {
+        let until = (*self.rate_limited_until.lock().unwrap())?;
+        until
+            .checked_duration_since(Instant::now())
+            .filter(|wait| !wait.is_zero())
+    }
+
+    pub fn back_off(&self, wait: Duration) {
+        let until = Instant::now() + wait.min(MAX_RATE_LIMIT_WAIT);
+        let mut current = self.rate_limited_until.lock().unwrap();
+        *current = Some(current.map_or(until, |current| current.max(until)));
+    }
+
+    pub fn next_page_by(&mut self, amount: u16) {
+        self.set_page(self.page.saturating_add(amount));
+    }
+
+    pub fn set_page(&mut self, page: u16) {
+        self.page = page.clamp(1, MAX_PAGE);
+    }
+
+    pub fn set_scope(&mut self, scope: Scope) {
+        self.scope = scope;
+        self.page = 1;
+    }
+
+    pub fn set_client(&mut self, client: reqwest::Client) {
+        self.client = client;
+    }
+
+    pub fn set_base_url(&mut self, base_url: reqwest::Url) {
+        self.base_url = base_url;
+    }
+
+    pub fn set_dates(
+        &mut self,
+        after: Option<NaiveDate>,
+        before: Option<NaiveDate>,
+    ) -> Result<(), String> {
+        if let (Some(after), Some(before)) = (after, before) {
+            if after > before {
+                return Err(format!("{} is after {}", after, before));
+            }
+        }
+        self.after = after;
+        self.before = before;
+        self.page = 1;
+        Ok(())
+    }
+
+    pub fn set_query<S: Into<String> + std::fmt::Display>(&mut self, query: S) {
+        self.query = sanitize_query(&query.to_string());
+    }
+}
+
+// pasted titles and urls can bring newlines, tabs and other control
+// characters along, and arbitrarily long text
+fn sanitize_query(query: &str) -> String {
+    let cleaned: String = query
+        .chars()
+        .map(|c| if c.is_control() { ' ' } else { c })
+        .collect();
+    let words: Vec<_> = cleaned.split_whitespace().collect();
+    let mut query = words.join(" ");
+    if let Some((end, _)) = query.char_indices().nth(MAX_QUERY_LENGTH) {
+        query.truncate(end);
+        query.truncate(query.trim_end().len());
+    }
+    query
+}
+
+// ~/.config/arxiv-cli/config.json
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct Config {
+    theme: ThemeConfig,
+    // like "zathura {file}" or "firefox {url}"
+    viewer_command: Option<String>,
+    // seconds, overridden by $ARXIV_CLI_TIMEOUT
+    timeout: Option<u64>,
+    // seconds a cached page is fresh for, overridden by $ARXIV_CLI_CACHE_TTL
+    cache_ttl: Option<u64>,
+    // like "cs.LG", for the recent papers shown by `b` and on a fresh start
+    home_category: Option<String>,
+    // a proxy url for every request, or "none" to ignore the proxy variables.
+    // overridden by $ARXIV_CLI_PROXY
+    proxy: Option<String>,
+    // false hides the ✅/❌ column, `V` toggles it either way
+    show_seen: Option<bool>,
+    // replace ✅/❌ for fonts without emoji, like "[x]"/"[ ]"
+    seen_marker: Option<String>,
+    unseen_marker: Option<String>,
+    // results shown per page, overridden by --limit
+    page_size: Option<usize>,
+    // true makes j at the last item go to the first and k at the first go to the last
+    wrap_around: Option<bool>,
+    // true makes j at the last item load the next page, `J` toggles it
+    auto_advance: Option<bool>,
+    // true asks before `q` quits a session that added bookmarks
+    confirm_quit: Option<bool>,
+}
+
+fn client(timeout: Duration, proxies: Vec<reqwest::Proxy>) -> reqwest::Result<reqwest::Client> {
+    // only the proxies worked out by `proxies`, not reqwest's own guesses
+    let mut builder = reqwest::Client::builder()
+        .user_agent(USER_AGENT)
+        .timeout(timeout)
+        .no_proxy();
+    for proxy in proxies {
+        builder = builder.proxy(proxy);
+    }
+    builder.build()
+}
+
+fn env_proxy(var: &str) -> Option<String> {
+    std::env::var(var)
+        .ok()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+// $ARXIV_CLI_PROXY or the config's proxy, with where it came from
+fn explicit_proxy(config: &Config) -> Option<(&'static str, String)> {
+    env_proxy(PROXY_VAR)
+        .map(|proxy| (PROXY_VAR, proxy))
+        .or_else(|| config.proxy.clone().map(|proxy| ("config", proxy)))
+}
+
+fn disables_proxy(proxy: &str) -> bool {
+    matches!(proxy.trim(), "none" | "off")
+}
+
+// the proxy most requests go through, for error messages
+fn describe_proxy(config: &Config) -> Option<String> {
+    let (source, proxy) = explicit_proxy(config).or_else(|| {
+        PROXY_VARS
+            .iter()
+            .find_map(|(_, var)| env_proxy(var).map(|proxy| (*var, proxy)))
+    })?;
+    (!disables_proxy(&proxy)).then(|| format!("{} from {}", proxy, source))
+}
+
+// an explicit proxy is used for everything, otherwise HTTPS_PROXY, HTTP_PROXY
+// and ALL_PROXY (or their lowercase forms) apply to their schemes. hosts in
+// NO_PROXY are always reached directly. a malformed url is an error here
+// rather than being silently skipped, and an unreachable proxy shows up as a
+// network error naming it.
+fn proxies(config: &Config) -> Result<Vec<reqwest::Proxy>, Box<dyn Error>> {
+    let invalid = |source: &str, proxy: &str, e: reqwest::Error| {
+        format!("{} is not a valid proxy url ({}): {}", source, proxy, e)
+    };
+    if let Some((source, proxy)) = explicit_proxy(config) {
+        if disables_proxy(&proxy) {
+            return Ok(vec![]);
+        }
+        let all = reqwest::Proxy::all(&proxy).map_err(|e| invalid(source, &proxy, e))?;
+        return Ok(vec![all.no_proxy(reqwest::NoProxy::from_env())]);
+    }
+
+    let mut proxies = vec![];
+    let mut schemes = HashSet::new();
+    for (scheme, var) in PROXY_VARS {
+        let Some(proxy) = env_proxy(var) else {
+            continue;
+        };
+        // HTTPS_PROXY wins over https_proxy
+        if !schemes.insert(*scheme) {
+            continue;
+        }
+        let intercept = match *scheme {
+            "https" => reqwest::Proxy::https(&proxy),
+            "http" => reqwest::Proxy::http(&proxy),
+            _ => reqwest::Proxy::all(&proxy),
+        }
+        .map_err(|e| invalid(var, &proxy, e))?;
+        proxies.push(intercept.no_proxy(reqwest::NoProxy::from_env()));
+    }
+    Ok(proxies)
+}
+
+fn timeout(config: &Config) -> Result<Duration, Box<dyn Error>> {
+    let seconds = match std::env::var(TIMEOUT_VAR) {
+        Ok(seconds) => seconds
+            .parse()
+            .map_err(|_| format!("{} is not a number of seconds: {}", TIMEOUT_VAR, seconds))?,
+        Err(_) => match config.timeout {
+            Some(seconds) => seconds,
+            None => return Ok(DEFAULT_TIMEOUT),
+        },
+    };
+    Ok(Duration::from_secs(seconds))
+}
+
+// a preset, with any of its colors overridden by name ("red", "lightblue")
+// or hex ("#ff8800")
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct ThemeConfig {
+    preset: Option<String>,
+    header_bg: Option<String>,
+    header_fg: Option<String>,
+    selection: Option<String>,
+    border: Option<String>,
+    seen_marker: Option<String>,
+}
+
+#[derive(Clone, Debug)]
+struct Theme {
+    header_bg: Color,
+    header_fg: Color,
+    // None keeps the selected row reversed instead of giving it a background
+    selection: Option<Color>,
+    border: Color,
+    seen_marker: Color,
+    // footer key hints, footer status and field labels in the detail view
+    hint: Color,
+    status: Color,
+    label: Color,
+    // background of the error banner, None reverses it instead
+    error: Option<Color>,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme {
+            header_bg: Color::Blue,
+            header_fg: Color::Red,
+            selection: None,
+            border: Color::Reset,
+            seen_marker: Color::Reset,
+            hint: Color::DarkGray,
+            status: Color::Yellow,
+            label: Color::Red,
+            error: Some(Color::Red),
+        }
+    }
+}
+
+impl Theme {
+    pub fn preset(name: &str) -> Option<Self> {
+        match name {
+            "default" => Some(Theme::default()),
+            "light" => Some(Theme {
+                header_bg: Color::Gray,
+                header_fg: Color::Black,
+                selection: Some(Color::LightCyan),
+                border: Color::DarkGray,
+                seen_marker: Color::Reset,
+                hint: Color::DarkGray,
+                status: Color::Blue,
+                label: Color::Red,
+                error: Some(Color::Red),
+            }),
+            "high-contrast" => Some(Theme {
+                header_bg: Color::White,
+                header_fg: Color::Black,
+                selection: Some(Color::Yellow),
+                border: Color::White,
+                seen_marker: Color::White,
+                hint: Color::White,
+                status: Color::Yellow,
+                label: Color::Yellow,
+                error: Some(Color::LightRed),
+            }),
+            // for NO_COLOR: only the reversed selection sets anything apart
+            "no-color" => Some(Theme {
+                header_bg: Color::Reset,
+                header_fg: Color::Reset,
+                selection: None,
+                border: Color::Reset,
+                seen_marker: Color::Reset,
+                hint: Color::Reset,
+                status: Color::Reset,
+                label: Color::Reset,
+                error: None,
+            }),
+            _ => None,
+        }
+    }
+
+    pub fn from_config(config: &ThemeConfig) -> Result<Self, String> {
+        let mut theme = match &config.preset {
+            Some(name) => Theme::preset(name).ok_or(format!("unknown theme preset: {}", name))?,
+            None => Theme::default(),
+        };
+        if let Some(color) = &config.header_bg {
+            theme.header_bg = parse_color(color)?;
+        }
+        if let Some(color) = &config.header_fg {
+            theme.header_fg = parse_color(color)?;
+        }
+        if let Some(color) = &config.selection {
+            theme.selection = Some(parse_color(color)?);
+        }
+        if let Some(color) = &config.border {
+            theme.border = parse_color(color)?;
+        }
+        if let Some(color) = &config.seen_marker {
+            theme.seen_marker = parse_color(color)?;
+        }
+        Ok(theme)
+    }
+
+    pub fn selected_style(&self) -> Style {
+        match self.selection {
+            Some(color) => Style::default().bg(color),
+            None => Style::default().add_modifier(Modifier::REVERSED),
+        }
+    }
+
+    pub fn error_style(&self) -> Style {
+        let style = Style::default().add_modifier(Modifier::BOLD);
+        match self.error {
+            Some(color) => style.fg(Color::White).bg(color),
+            None => style.add_modifier(Modifier::REVERSED),
+        }
+    }
+}
+
+fn parse_color(color: &str) -> Result<Color, String> {
+    let color = color.trim().to_lowercase();
+    if let Some(hex) = color.strip_prefix('#') {
+        let channel = |i: usize| {
+            hex.get(i..i + 2)
+                .and_then(|channel| u8::from_str_radix(channel, 16).ok())
+        };
+        return match (hex.len(), channel(0), channel(2), channel(4)) {
+            (6, Some(r), Some(g), Some(b)) => Ok(Color::Rgb(r, g, b)),
+            _ => Err(format!("not a hex color: #{}", hex)),
+        };
+    }
+    Ok(match color.as_str() {
+        "reset" | "default" => Color::Reset,
+        "black" => Color::Black,
+        "red" => Color::Red,
+        "green" => Color::Green,
+        "yellow" => Color::Yellow,
+        "blue" => Color::Blue,
+        "magenta" => Color::Magenta,
+        "cyan" => Color::Cyan,
+        "gray" | "grey" => Color::Gray,
+        "darkgray" | "darkgrey" => Color::DarkGray,
+        "lightred" => Color::LightRed,
+        "lightgreen" => Color::LightGreen,
+        "lightyellow" => Color::LightYellow,
+        "lightblue" => Color::LightBlue,
+        "lightmagenta" => Color::LightMagenta,
+        "lightcyan" => Color::LightCyan,
+        "white" => Color::White,
+        _ => return Err(format!("unknown color: {}", color)),
+    })
+}
+
+fn load_config() -> Result<Config, Box<dyn Error>> {
+    let path = match dirs::config_dir() {
+        Some(dir) => dir.join(CONFIG_PATH),
+        None => return Ok(Config::default()),
+    };
+    match std::fs::read_to_string(&path) {
+        Ok(contents) => {
+            serde_json::from_str(&contents).map_err(|e| format!("{}: {}", path.display(), e).into())
+        }
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Config::default()),
+        Err(e) => Err(format!("{}: {}", path.display(), e).into()),
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Session {
+    query: String,
+    page: u16,
+    #[serde(default)]
+    history: Vec<String>,
+    #[serde(default)]
+    collection: Option<String>,
+    #[serde(default)]
+    positions: HashMap<String, Position>,
+    #[serde(default)]
+    density: Density,
+    #[serde(default)]
+    scope: Scope,
+    // seconds since the epoch of the last look at each query
+    #[serde(default)]
+    visits: HashMap<String, i64>,
+}
+
+// where the user left off in a query they searched before
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct Position {
+    page: u16,
+    selected: Option<String>,
+}
+
+fn session_path() -> Option<PathBuf> {
+    data_file(SESSION_FILE)
+}
+
+fn load_session() -> Option<Session> {
+    let contents = std::fs::read_to_string(session_path()?).ok()?;
+    serde_json::from_str(&contents).ok()
+}
+
+// everything written out on quit
+fn save_state(params: &Params, app: &App) -> Result<(), Box<dyn Error>> {
+    app.save_ids()?;
+    app.save_notes()?;
+    app.save_read()?;
+    save_session(params, app)
+}
+
+fn save_session(params: &Params, app: &App) -> Result<(), Box<dyn Error>> {
+    if let Some(path) = session_path() {
+        let session = Session {
+            query: params.query.clone(),
+            page: params.page,
+            history: app.history.clone(),
+            collection: Some(app.collection.clone()),
+            positions: app.positions(),
+            density: app.density,
+            scope: params.scope,
+            visits: app.visits(&params.query),
+        };
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, serde_json::to_string(&session)?)?;
+    }
+    Ok(())
+}
+
+struct App {
+    state: TableState,
+    items: Responses,
+    current: Option<usize>,
+    ids: Ids,
+    status: String,
+    // why the last fetch of the page failed, until one succeeds
+    last_error: Option<String>,
+    show_seen: bool,
+    seen_marker: String,
+    unseen_marker: String,
+    wrap_around: bool,
+    // j past the last item loads the next page
+    auto_advance: bool,
+    // bookmarks added since starting, for the quit confirmation
+    added: usize,
+    confirm_quit: bool,
+    // described in connection errors, since a bad proxy looks like being offline
+    proxy: Option<String>,
+    // the what's new notice, until a key is pressed
+    whats_new: bool,
+    loading: bool,
+    saved_only: bool,
+    page_items: Responses,
+    sort: SortMode,
+    rank: HashMap<String, usize>,
+    offset: usize,
+    table_area: Rect,
+    row_height: u16,
+    history: Vec<String>,
+    stale: bool,
+    theme: Theme,
+    // ids marked with space for batch save/open/export
+    marked: HashSet<String>,
+    viewer: Option<String>,
+    // the full page while a `,` filter is narrowing items
+    unfiltered: Option<Responses>,
+    raw_latex: bool,
+    notes: Notes,
+    // papers already skimmed, separate from the bookmarks in `ids`
+    read: Read,
+    // infinite scroll: reaching the last row fetches and appends the next page
+    append: bool,
+    // pages appended after the first one currently shown
+    appended: u16,
+    // the last append came back empty or failed, so stop asking
+    exhausted: bool,
+    // the params behind the items on screen, restored when a load is cancelled
+    shown: Option<Params>,
+    // the named reading list `ids` was loaded from
+    collection: String,
+    home_category: Option<String>,
+    // the next page, fetched in the background while this one is read
+    prefetch: Option<Prefetch>,
+    // per query, only kept for queries still in the history
+    positions: HashMap<String, Position>,
+    // when each query was last looked at in an earlier session, papers since then are new
+    last_visits: HashMap<String, i64>,
+    // the queries looked at this session, the next session's last visits
+    visits: HashMap<String, i64>,
+    density: Density,
+}
+
+struct Prefetch {
+    key: u64,
+    handle: tokio::task::JoinHandle<Option<(Responses, Fetched)>>,
+}
+
+// mirrors tui's Table::get_row_bounds for rows of a uniform height, since
+// TableState doesn't expose the offset it scrolled to
+fn table_offset(
+    offset: usize,
+    selected: Option<usize>,
+    len: usize,
+    row_height: u16,
+    total_height: u16,
+    max_height: u16,
+) -> usize {
+    if len == 0 {
+        return 0;
+    }
+    let mut start = offset.min(len - 1);
+    let mut end = start;
+    let mut height: u16 = 0;
+    while end < len && height + row_height <= max_height {
+        height += total_height;
+        end += 1;
+    }
+
+    let selected = selected.unwrap_or(0).min(len - 1);
+    while selected >= end {
+        height = height.saturating_add(total_height);
+        end += 1;
+        while height > max_height {
+            height = height.saturating_sub(total_height);
+            start += 1;
+        }
+    }
+    while selected < start {
+        start -= 1;
+        height = height.saturating_add(total_height);
+        // rows drop off the bottom, which only matters for the height
+        while height > max_height {
+            height = height.saturating_sub(total_height);
+        }
+    }
+    start
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum SortMode {
+    // the order the backend returned
+    #[default]
+    Relevance,
+    Updated,
+    Published,
+    Title,
+}
+
+impl SortMode {
+    pub fn next(self) -> Self {
+        match self {
+            SortMode::Relevance => SortMode::Updated,
+            SortMode::Updated => SortMode::Published,
+            SortMode::Published => SortMode::Title,
+            SortMode::Title => SortMode::Relevance,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SortMode::Relevance => "relevance",
+            SortMode::Updated => "updated",
+            SortMode::Published => "published",
+            SortMode::Title => "title",
+        }
+    }
+}
+
+// presets for how much of each paper the list shows
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Density {
+    Compact,
+    #[default]
+    Normal,
+    Detailed,
+}
+
+impl Density {
+    pub fn next(self) -> Self {
+        match self {
+            Density::Compact => Density::Normal,
+            Density::Normal => Density::Detailed,
+            Density::Detailed => Density::Compact,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Density::Compact => "compact",
+            Density::Normal => "normal",
+            Density::Detailed => "detailed",
+        }
+    }
+
+    // the summary preview gets as many lines as the row is tall
+    pub fn row_height(self) -> u16 {
+        match self {
+            Density::Compact => 1,
+            Density::Normal => ROW_HEIGHT,
+            Density::Detailed => DETAILED_ROW_HEIGHT,
+        }
+    }
+
+    pub fn max_authors(self) -> usize {
+        match self {
+            Density::Compact => 1,
+            Density::Normal => MAX_AUTHORS,
+            Density::Detailed => DETAILED_MAX_AUTHORS,
+        }
+    }
+}
+
+// saved papers keyed by id. papers saved before full responses were persisted
+// have no data until they show up in a fetched page again.
+pub type Ids = HashMap<String, Option<Response>>;
+
+// the default collection is the plain bookmarks file
+fn collection_path(name: &str) -> Option<PathBuf> {
+    if name == DEFAULT_COLLECTION {
+        data_file(BOOKMARKS_FILE)
+    } else {
+        data_file(COLLECTIONS_DIR).map(|dir| dir.join(format!("{}.json", name)))
+    }
+}
+
+// names end up in file names, so keep them to something boring
+fn valid_collection_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_'))
+}
+
+fn collections() -> Vec<String> {
+    let mut names = vec![DEFAULT_COLLECTION.to_string()];
+    let dir = data_file(COLLECTIONS_DIR);
+    if let Some(entries) = dir.and_then(|dir| std::fs::read_dir(dir).ok()) {
+        let mut found: Vec<_> = entries
+            .flatten()
+            .filter_map(|entry| {
+                let path = entry.path();
+                let stem = path.file_stem()?.to_str()?.to_string();
+                (path.extension()? == "json" && stem != DEFAULT_COLLECTION).then_some(stem)
+            })
+            .collect();
+        found.sort();
+        names.extend(found);
+    }
+    names
+}
+
+async fn get_ids(collection: &str) -> Ids {
+    if let Some(path) = collection_path(collection) {
+        if let Ok(id) = std::fs::read_to_string(path) {
+            match serde_json::from_str(&id) {
+                Ok(ids) => ids,
+                // migrate the old newline delimited list of ids
+                Err(_) => id.lines().map(|url| (url.to_string(), None)).collect(),
+            }
+        } else {
+            Ids::default()
+        }
+    } else {
+        Ids::default()
+    }
+}
+
+fn write_ids(collection: &str, ids: &Ids) -> std::io::Result<()> {
+    if let Some(path) = collection_path(collection) {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let mut arxiv_file = File::options()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(path)?;
+        serde_json::to_writer(&mut arxiv_file, ids)?;
+    };
+    Ok(())
+}
+
+// notes keyed by the bare arXiv id, so they survive new versions of a paper
+pub type Notes = HashMap<String, String>;
+
+fn note_key(id: &str) -> String {
+    strip_version(arxiv_id(id)).to_string()
+}
+
+async fn get_notes() -> Notes {
+    data_file(NOTES_FILE)
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|notes| serde_json::from_str(&notes).ok())
+        .unwrap_or_default()
+}
+
+pub type Read = HashSet<String>;
+
+// None until something has been marked read for the first time
+async fn get_read() -> Option<Read> {
+    let read = std::fs::read_to_string(data_file(READ_FILE)?).ok()?;
+    serde_json::from_str(&read).ok()
+}
+
+impl App {
+    fn new() -> App {
+        App {
+            state: TableState::default(),
+            items: vec![],
+            current: None,
+            ids: Ids::new(),
+            status: String::new(),
+            last_error: None,
+            show_seen: true,
+            seen_marker: SEEN_MARKER.to_string(),
+            unseen_marker: UNSEEN_MARKER.to_string(),
+            wrap_around: false,
+            auto_advance: false,
+            added: 0,
+            confirm_quit: false,
+            proxy: None,
+            whats_new: false,
+            loading: false,
+            saved_only: false,
+            page_items: vec![],
+            sort: SortMode::default(),
+            rank: HashMap::new(),
+            offset: 0,
+            table_area: Rect::default(),
+            row_height: ROW_HEIGHT,
+            history: vec![],
+            stale: false,
+            theme: Theme::default(),
+            marked: HashSet::new(),
+            viewer: None,
+            unfiltered: None,
+            raw_latex: false,
+            notes: Notes::new(),
+            read: Read::new(),
+            append: false,
+            appended: 0,
+            exhausted: false,
+            shown: None,
+            collection: DEFAULT_COLLECTION.to_string(),
+            home_category: None,
+            prefetch: None,
+            positions: HashMap::new(),
+            last_visits: HashMap::new(),
+            visits: HashMap::new(),
+            density: Density::default(),
+        }
+    }
+
+    pub fn save_ids(&self) -> std::io::Result<()> {
+        write_ids(&self.collection, &self.ids)
+    }
+
+    // `ids` are the bookmarks already loaded from the collection being switched to
+    pub fn switch_collection(&mut self, name: String, ids: Ids) {
+        self.set_ids(ids);
+        self.collection = name;
+        if self.saved_only {
+            // rebuild the saved-only view from the new collection
+            self.toggle_saved_only();
+            self.toggle_saved_only();
+        }
+        self.set_status(format!("switched to collection {}", self.collection));
+    }
+
+    // adds the targets to another collection without switching to it
+    pub async fn add_to_collection(&mut self, name: &str) {
+        let targets = self.targets();
+        if targets.is_empty() {
+            self.set_status(NOTHING_SELECTED);
+            return;
+        }
+        if name == self.collection {
+            self.save_selected();
+            return;
+        }
+        let mut ids = get_ids(name).await;
+        let count = targets.len();
+        for item in targets {
+            ids.insert(item.id.clone(), Some(item));
+        }
+        match write_ids(name, &ids) {
+            Ok(()) => self.set_status(format!("added {} to {}", count, name)),
+            Err(e) => self.set_status(format!("couldn't save to {}: {}", name, e)),
+        }
+    }
+
+    pub fn save_notes(&self) -> std::io::Result<()> {
+        if let Some(path) = data_file(NOTES_FILE) {
+            if let Some(dir) = path.parent() {
+                std::fs::create_dir_all(dir)?;
+            }
+            let file = File::create(path)?;
+            serde_json::to_writer(file, &self.notes)?;
+        }
+        Ok(())
+    }
+
+    pub fn save_read(&self) -> std::io::Result<()> {
+        if let Some(path) = data_file(READ_FILE) {
+            if let Some(dir) = path.parent() {
+                std::fs::create_dir_all(dir)?;
+            }
+            let file = File::create(path)?;
+            serde_json::to_writer(file, &self.read)?;
+        }
+        Ok(())
+    }
+
+    // marked (●), saved (★) and has a note (✎), for the first column
+    pub fn markers(&self, id: &str) -> String {
+        [
+            (self.marked.contains(id), "●"),
+            (self.ids.contains_key(id), "★"),
+            (self.note(id).is_some(), "✎"),
+        ]
+        .into_iter()
+        .filter_map(|(shown, badge)| shown.then_some(badge))
+        .collect()
+    }
+
+    pub fn note(&self, id: &str) -> Option<&str> {
+        self.notes.get(&note_key(id)).map(String::as_str)
+    }
+
+    // an empty note removes it, anything else also saves the paper
+    pub fn set_note(&mut self, item: Response, note: &str) {
+        let note = note.trim();
+        if note.is_empty() {
+            self.notes.remove(&note_key(&item.id));
+        } else {
+            self.notes.insert(note_key(&item.id), note.to_string());
+            self.add_item(item);
+        }
+        self.autosave();
+    }
+
+    pub fn set_ids(&mut self, ids: Ids) {
+        self.ids = ids;
+    }
+
+    // bookmarks, notes and read papers are written as soon as they change so a crash can't lose them
+    pub fn autosave(&mut self) {
+        if let Err(e) = self
+            .save_ids()
+            .and_then(|_| self.save_notes())
+            .and_then(|_| self.save_read())
+        {
+            self.set_status(format!("couldn't save bookmarks: {}", e));
+        }
+    }
+
+    pub fn add_item(&mut self, item: Response) {
+        if self.ids.insert(item.id.clone(), Some(item)).is_none() {
+            self.added += 1;
+        }
+    }
+
+    pub fn remove_id(&mut self, id: String) {
+        self.ids.remove(&id);
+        self.autosave();
+    }
+
+    pub fn set_status<S: Into<String>>(&mut self, status: S) {
+        self.status = status.into();
+    }
+
+    pub fn set_error(&mut self, e: &(dyn Error + 'static)) {
+        let mut error = format!("network error: {}", describe_error(e));
+        let connect = e
+            .downcast_ref::<reqwest::Error>()
+            .is_some_and(|e| e.is_connect());
+        if let Some(proxy) = self.proxy.as_ref().filter(|_| connect) {
+            error.push_str(&format!(" (through proxy {})", proxy));
+        }
+        error!("{}", error);
+        self.last_error = Some(error);
+    }
+
+    pub fn clear_error(&mut self) {
+        self.last_error = None;
+    }
+
+    pub fn push_history(&mut self, query: &str) {
+        if query.is_empty() || self.history.last().map(String::as_str) == Some(query) {
+            return;
+        }
+        self.history.push(query.to_string());
+        if self.history.len() > MAX_HISTORY {
+            self.history.remove(0);
+        }
+    }
+
+    pub fn set_stale(&mut self, stale: bool) {
+        self.stale = stale;
+    }
+
+    pub fn set_loading(&mut self, loading: bool) {
+        self.loading = loading;
+    }
+
+    // a refreshed page keeps the selected paper if it's still on it, anything
+    // else (a new page or a new search) starts back at the top
+    pub fn update_items(&mut self, items: Responses) {
+        let selected = self.selected().map(|item| item.id.clone());
+        for item in items.iter() {
+            if let Some(saved @ None) = self.ids.get_mut(&item.id) {
+                *saved = Some(item.clone());
+            }
+        }
+        self.rank = items
+            .iter()
+            .enumerate()
+            .map(|(i, item)| (item.id.clone(), i))
+            .collect();
+        self.items = items;
+        self.appended = 0;
+        self.exhausted = false;
+        self.saved_only = false;
+        self.page_items = vec![];
+        self.unfiltered = None;
+        self.marked.retain(|id| self.rank.contains_key(id));
+        self.state = TableState::default();
+        self.offset = 0;
+        let i = selected.and_then(|id| self.items.iter().position(|item| item.id == id));
+        self.select_clamped(Some(i.unwrap_or(0)));
+        self.sort_items();
+    }
+
+    pub fn cycle_sort(&mut self) {
+        self.set_sort(self.sort.next());
+    }
+
+    pub fn set_sort(&mut self, sort: SortMode) {
+        self.sort = sort;
+        self.sort_items();
+    }
+
+    pub fn selected(&self) -> Option<&Response> {
+        self.current.and_then(|i| self.items.get(i))
+    }
+
+    pub fn toggle_mark(&mut self) {
+        if let Some(id) = self.selected().map(|item| item.id.clone()) {
+            if !self.marked.remove(&id) {
+                self.marked.insert(id);
+            }
+        }
+    }
+
+    pub fn clear_marks(&mut self) {
+        self.marked.clear();
+    }
+
+    // the marked papers on this page, or just the selected one if none are marked
+    pub fn targets(&self) -> Responses {
+        if self.marked.is_empty() {
+            self.selected().cloned().into_iter().collect()
+        } else {
+            self.items
+                .iter()
+                .filter(|item| self.marked.contains(&item.id))
+                .cloned()
+                .collect()
+        }
+    }
+
+    // every paper on the page at once, for after skimming the whole thing
+    pub fn read_page(&mut self) {
+        let before = self.read.len();
+        self.read
+            .extend(self.items.iter().map(|item| item.id.clone()));
+        self.set_status(format!(
+            "marked {} papers as seen",
+            self.read.len() - before
+        ));
+        self.autosave();
+    }
+
+    pub fn unread_page(&mut self) {
+        let before = self.read.len();
+        for item in &self.items {
+            self.read.remove(&item.id);
+        }
+        self.set_status(format!("unmarked {} papers", before - self.read.len()));
+        self.autosave();
+    }
+
+    pub fn save_selected(&mut self) {
+        let targets = self.targets();
+        if targets.is_empty() {
+            self.set_status(NOTHING_SELECTED);
+            return;
+        }
+        if targets.len() > 1 {
+            self.set_status(format!("saved {} papers", targets.len()));
+        }
+        for item in targets {
+            self.add_item(item);
+        }
+        self.autosave();
+    }
+
+    // marks the targets read, or unread if they all already are
+    pub fn toggle_read(&mut self) {
+        let targets = self.targets();
+        if targets.is_empty() {
+            self.set_status(NOTHING_SELECTED);
+            return;
+        }
+        let unread = targets.iter().any(|item| !self.read.contains(&item.id));
+        for item in &targets {
+            if unread {
+                self.read.insert(item.id.clone());
+            } else {
+                self.read.remove(&item.id);
+            }
+        }
+        let papers = match targets.len() {
+            1 => "1 paper".to_string(),
+            n => format!("{} papers", n),
+        };
+        let state = if unread { "seen" } else { "unseen" };
+        self.set_status(format!("marked {} as {}", papers, state));
+        self.autosave();
+    }
+
+    pub fn open_selected_pdf(&mut self) {
+        let targets = self.targets();
+        if targets.is_empty() {
+            self.set_status(NOTHING_SELECTED);
+            return;
+        }
+
+        let hrefs: Vec<_> = targets
+            .iter()
+            .filter_map(pdf_link)
+            .map(|link| link.href.clone())
+            .collect();
+        for href in hrefs.iter() {
+            if let Err(e) = open_url(self.viewer.as_deref(), href) {
+                self.set_status(e.to_string());
+                return;
+            }
+        }
+        match hrefs.as_slice() {
+            [] if targets.len() == 1 => self.set_status("this paper has no pdf link"),
+            [] => self.set_status("none of these papers have a pdf link"),
+            [href] => self.set_status(format!("opened {}", href)),
+            hrefs => self.set_status(format!("opened {} pdfs", hrefs.len())),
+        }
+    }
+
+    // re-sorts items, keeping the selection on the same paper
+    fn sort_items(&mut self) {
+        let selected = self.selected().map(|item| item.id.clone());
+
+        match self.sort {
+            SortMode::Relevance => {
+                let rank = &self.rank;
+                self.items
+                    .sort_by_key(|item| rank.get(&item.id).copied().unwrap_or(usize::MAX));
+            }
+            SortMode::Updated => self.items.sort_by(|a, b| b.updated.cmp(&a.updated)),
+            SortMode::Published => self.items.sort_by(|a, b| b.published.cmp(&a.published)),
+            SortMode::Title => self.items.sort_by_key(|item| item.title.to_lowercase()),
+        }
+
+        if let Some(i) = selected.and_then(|id| self.items.iter().position(|item| item.id == id)) {
+            self.current = Some(i);
+            self.state.select(Some(i));
+        }
+    }
+
+    // swaps the fetched page out for just the bookmarked papers, and back again
+    pub fn toggle_saved_only(&mut self) {
+        self.clear_filter();
+        if self.saved_only {
+            self.items = std::mem::take(&mut self.page_items);
+        } else {
+            self.page_items = std::mem::take(&mut self.items);
+            self.items = self.ids.values().flatten().cloned().collect();
+            self.items.sort_by(|a, b| b.updated.cmp(&a.updated));
+        }
+        self.saved_only = !self.saved_only;
+        self.sort_items();
+        let selected = if self.items.is_empty() { None } else { Some(0) };
+        self.current = selected;
+        self.state.select(selected);
+    }
+
+    pub fn toggle_append(&mut self) {
+        self.append = !self.append;
+        self.exhausted = false;
+        self.set_status(if self.append {
+            "infinite scroll on"
+        } else {
+            "infinite scroll off"
+        });
+    }
+
+    pub fn toggle_auto_advance(&mut self) {
+        self.auto_advance = !self.auto_advance;
+        self.set_status(if self.auto_advance {
+            "j on the last item goes to the next page"
+        } else {
+            "j stops at the last item"
+        });
+    }
+
+    // on the last item of a page that can be paged past
+    pub fn at_page_end(&self, params: &Params) -> bool {
+        !self.items.is_empty()
+            && self.current == Some(self.items.len() - 1)
+            && !self.saved_only
+            && !self.is_filtered()
+            && params.page < MAX_PAGE
+    }
+
+    pub fn wants_more_items(&self) -> bool {
+        self.append
+            && !self.exhausted
+            && !self.saved_only
+            && !self.is_filtered()
+            && !self.items.is_empty()
+            && self.current == Some(self.items.len() - 1)
+    }
+
+    // adds the next page below the current items, skipping any already shown
+    pub fn append_items(&mut self, items: Responses) {
+        let start = self.rank.len();
+        let new: Responses = items
+            .into_iter()
+            .filter(|item| !self.rank.contains_key(&item.id))
+            .collect();
+        for (i, item) in new.iter().enumerate() {
+            if let Some(saved @ None) = self.ids.get_mut(&item.id) {
+                *saved = Some(item.clone());
+            }
+            self.rank.insert(item.id.clone(), start + i);
+        }
+        self.items.extend(new);
+        self.appended += 1;
+        self.sort_items();
+    }
+
+    // starts fetching the page after `params`, replacing any other prefetch
+    pub fn prefetch_next(&mut self, params: &Params) {
+        let mut next = params.clone();
+        next.next_page_by(1);
+        if next.page == params.page {
+            return;
+        }
+        let key = page_key(&next);
+        if self
+            .prefetch
+            .as_ref()
+            .is_some_and(|prefetch| prefetch.key == key)
+        {
+            return;
+        }
+        self.cancel_prefetch();
+        let handle = tokio::spawn(async move { get_items(&next, false).await.ok() });
+        self.prefetch = Some(Prefetch { key, handle });
+    }
+
+    pub fn cancel_prefetch(&mut self) {
+        if let Some(prefetch) = self.prefetch.take() {
+            prefetch.handle.abort();
+        }
+    }
+
+    // the prefetch for `params`, if that's the page being prefetched
+    pub fn take_prefetch(&mut self, params: &Params) -> Option<Prefetch> {
+        match self.prefetch.take() {
+            Some(prefetch) if prefetch.key == page_key(params) => Some(prefetch),
+            Some(prefetch) => {
+                prefetch.handle.abort();
+                None
+            }
+            None => None,
+        }
+    }
+
+    // the page and paper on screen for the query that's shown
+    fn current_position(&self) -> Option<(String, Position)> {
+        let shown = self.shown.as_ref()?;
+        let position = Position {
+            page: shown.page,
+            selected: self.selected().map(|item| item.id.clone()),
+        };
+        Some((shown.query.clone(), position))
+    }
+
+    pub fn remember_position(&mut self) {
+        if let Some((query, position)) = self.current_position() {
+            self.positions.insert(query, position);
+        }
+    }
+
+    // reselects the paper remembered for `params`, if it's on this page
+    pub fn restore_position(&mut self, params: &Params) {
+        let id = match self.positions.get(&params.query) {
+            Some(Position {
+                page,
+                selected: Some(id),
+            }) if *page == params.page => id,
+            _ => return,
+        };
+        if let Some(i) = self.items.iter().position(|item| item.id == *id) {
+            self.select(i);
+        }
+    }
+
+    // the positions worth saving, including the current one
+    pub fn positions(&self) -> HashMap<String, Position> {
+        let mut positions = self.positions.clone();
+        positions.extend(self.current_position());
+        positions.retain(|query, _| self.history.contains(query));
+        positions
+    }
+
+    pub fn visit(&mut self, query: &str) {
+        self.visits
+            .insert(query.to_string(), chrono::Utc::now().timestamp());
+    }
+
+    // published since `query` was last looked at, never true on a first look
+    pub fn is_new(&self, query: &str, item: &Response) -> bool {
+        let Some(since) = self.last_visits.get(query) else {
+            return false;
+        };
+        chrono::DateTime::parse_from_rfc3339(&item.published)
+            .is_ok_and(|published| published.timestamp() > *since)
+    }
+
+    // the visits worth saving, like positions only for queries still in the history
+    pub fn visits(&self, current: &str) -> HashMap<String, i64> {
+        let mut visits = self.last_visits.clone();
+        visits.extend(self.visits.clone());
+        visits.retain(|query, _| query == current || self.history.contains(query));
+        visits
+    }
+
+    pub fn set_density(&mut self, density: Density) {
+        self.density = density;
+        self.row_height = density.row_height();
+    }
+
+    pub fn toggle_seen_column(&mut self) {
+        self.show_seen = !self.show_seen;
+        if self.show_seen {
+            self.set_status("showing the seen column");
+        } else {
+            self.set_status("hid the seen column (X still marks papers seen)");
+        }
+    }
+
+    pub fn cycle_density(&mut self) {
+        self.set_density(self.density.next());
+        self.set_status(format!("{} density", self.density.label()));
+    }
+
+    pub fn toggle_raw_latex(&mut self) {
+        self.raw_latex = !self.raw_latex;
+    }
+
+    // the title or summary as it should be drawn
+    pub fn display_text(&self, text: &str) -> String {
+        if self.raw_latex {
+            text.to_string()
+        } else {
+            prettify_latex(text)
+        }
+    }
+
+    pub fn display_item(&self, item: &Response) -> Response {
+        Response {
+            title: self.display_text(&item.title),
+            summary: self.display_text(&item.summary),
+            ..item.clone()
+        }
+    }
+
+    pub fn is_filtered(&self) -> bool {
+        self.unfiltered.is_some()
+    }
+
+    // narrows the items to the ones whose title or authors fuzzy match `filter`
+    pub fn set_filter(&mut self, filter: &str) {
+        let all = self
+            .unfiltered
+            .get_or_insert_with(|| self.items.clone())
+            .clone();
+        self.items = all
+            .into_iter()
+            .filter(|item| {
+                let authors: Vec<_> = item.authors.iter().flatten().map(|x| x.as_str()).collect();
+                fuzzy_match(filter, &format!("{} {}", item.title, authors.join(" ")))
+            })
+            .collect();
+        self.sort_items();
+        self.select_clamped(Some(0));
+    }
+
+    pub fn clear_filter(&mut self) {
+        if let Some(items) = self.unfiltered.take() {
+            let selected = self.selected().map(|item| item.id.clone());
+            self.items = items;
+            let i = selected.and_then(|id| self.items.iter().position(|item| item.id == id));
+            self.select_clamped(Some(i.unwrap_or(0)));
+            self.sort_items();
+        }
+    }
+
+    // a row height of 1 is the compact mode, which packs rows without margins
+    pub fn is_compact(&self) -> bool {
+        self.row_height == 1
+    }
+
+    pub fn row_margin(&self) -> u16 {
+        if self.is_compact() {
+            0
+        } else {
+            1
+        }
+    }
+
+    // how many whole rows fit in the table as it was last drawn
+    pub fn visible_rows(&self) -> usize {
+        let height = self.table_area.height.saturating_sub(2 + HEADER_HEIGHT);
+        // the last row doesn't need a margin below it
+        let rows = (height + self.row_margin()) / (self.row_height + self.row_margin());
+        rows.max(1) as usize
+    }
+
+    pub fn grow_rows(&mut self) {
+        self.row_height = (self.row_height + 1).min(MAX_ROW_HEIGHT);
+    }
+
+    pub fn shrink_rows(&mut self) {
+        self.row_height = self.row_height.saturating_sub(1).max(1);
+    }
+
+    pub fn select(&mut self, i: usize) {
+        self.current = Some(i);
+        self.state.select(Some(i));
+    }
+
+    // the index of the row drawn at the given terminal cell, if any
+    pub fn row_at(&self, column: u16, row: u16) -> Option<usize> {
+        let area = self.table_area;
+        let top = area.y + 1 + HEADER_HEIGHT;
+        let inside = column > area.x
+            && column < area.right().saturating_sub(1)
+            && row >= top
+            && row < area.bottom().saturating_sub(1);
+        if !inside {
+            return None;
+        }
+        let y = row - top;
+        // clicks on the margin between rows don't select anything
+        let total_height = self.row_height + self.row_margin();
+        if y % total_height >= self.row_height {
+            return None;
+        }
+        let i = self.offset + (y / total_height) as usize;
+        if i < self.items.len() {
+            Some(i)
+        } else {
+            None
+        }
+    }
+
+    // every navigation method leaves nothing selected on an empty list
+    fn select_clamped(&mut self, i: Option<usize>) {
+        let selected = match self.items.len() {
+            0 => None,
+            len => i.map(|i| i.min(len - 1)),
+        };
+        self.current = selected;
+        self.state.select(selected);
+    }
+
+    pub fn first_item(&mut self) {
+        self.select_clamped(Some(0));
+    }
+
+    pub fn last_item(&mut self) {
+        self.select_clamped(self.items.len().checked_sub(1));
+    }
+
+    pub fn next_by(&mut self, amount: usize) {
+        let i = match self.state.selected() {
+            Some(i) => i.saturating_add(amount),
+            None => 0,
+        };
+        self.select_clamped(Some(i));
+    }
+
+    pub fn previous_by(&mut self, amount: usize) {
+        let i = match self.state.selected() {
+            Some(i) => i.saturating_sub(amount),
+            None => 0,
+        };
+        self.select_clamped(Some(i));
+    }
+
+    // like next_by, but past the last item goes back to the top with `wrap_around`
+    pub fn down(&mut self, amount: usize) {
+        let last = self.items.len().checked_sub(1);
+        if self.wrap_around && last.is_some() && self.current == last {
+            self.first_item();
+        } else {
+            self.next_by(amount);
+        }
+    }
+
+    pub fn up(&mut self, amount: usize) {
+        if self.wrap_around && !self.items.is_empty() && self.current == Some(0) {
+            self.last_item();
+        } else {
+            self.previous_by(amount);
+        }
+    }
+}
+
+const USAGE: &str =
+    "usage: arxiv-cli [--query <query>] [--category <category>] [--page <page>] [--limit <n>] [--no-color] [--log-file <path>]
+       arxiv-cli search <query> [--category <category>] [--page <page>] [--limit <n>] [--json]";
+const HELP: &str = "browse arXiv papers in the terminal, or print a search with `search`
+
+options:
+  -q, --query <query>        start with this search instead of the last session's
+  -c, --category <category>  only show papers in a category, like cs.LG
+  -p, --page <page>          start on this page of results
+      --no-color             don't use colors (also set by NO_COLOR)
+      --log-file <path>      append requests, errors and key presses to a file
+                             for bug reports (also set by ARXIV_CLI_LOG)
+  -n, --limit <n>            keep at most n results per page (1 to 100, also
+                             `page_size` in the config). the backend always
+                             sends a full page, so this trims rather than
+                             saves bandwidth
+      --json                 search: print the results as json
+  -h, --help                 print this help
+  -V, --version              print the version
+
+proxies:
+  HTTPS_PROXY, HTTP_PROXY and ALL_PROXY are used for their schemes, skipping
+  hosts in NO_PROXY. ARXIV_CLI_PROXY (or `proxy` in the config) sends every
+  request through one proxy instead, or set it to `none` to ignore them all.";
+
+// command line overrides for the restored session
+#[derive(Debug, Default)]
+struct Args {
+    query: Option<String>,
+    category: Option<String>,
+    page: Option<u16>,
+    help: bool,
+    version: bool,
+    no_color: bool,
+    log_file: Option<PathBuf>,
+    // `search` prints results instead of starting the tui
+    search: bool,
+    json: bool,
+    limit: Option<usize>,
+}
+
+// accepts both `--flag value` and `--flag=value`
+fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Args, String> {
+    let mut parsed = Args::default();
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let (flag, inline) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => {
+                (flag.to_string(), Some(value.to_string()))
+            }
+            _ => (arg, None),
+        };
+        let mut value = || {
+            inline
+                .clone()
+                .or_else(|| args.next())
+                .ok_or_else(|| format!("{} needs a value\n{}", flag, USAGE))
+        };
+        match flag.as_str() {
+            "-h" | "--help" => parsed.help = true,
+            "-V" | "--version" => parsed.version = true,
+            "--no-color" => parsed.no_color = true,
+            "--json" => parsed.json = true,
+            "search" if !parsed.search => parsed.search = true,
+            query if parsed.search && parsed.query.is_none() && !query.starts_with('-') => {
+                parsed.query = Some(query.to_string())
+            }
+            "-n" | "--limit" => {
+                let limit = value()?;
+                let limit = limit
+                    .parse()
+                    .map_err(|_| format!("--limit must be a number, got {}", limit))?;
+                parsed.limit = Some(check_page_size(limit)?);
+            }
+            "-q" | "--query" => parsed.query = Some(value()?),
+            "--log-file" => parsed.log_file = Some(PathBuf::from(value()?)),
+            "-c" | "--category" => parsed.category = Some(value()?),
+            "-p" | "--page" => {
+                let page = value()?;
+                let page = page
+                    .parse()
+                    .map_err(|_| format!("--page must be a number, got {}", page))?;
+                parsed.page = Some(page);
+            }
+            _ => return Err(format!("unknown argument {}\n{}", flag, USAGE)),
+        }
+    }
+    Ok(parsed)
+}
+
+impl Args {
+    // a new query or category starts from page 1 unless a page was given
+    fn apply(self, params: &mut Params) {
+        let query = match (self.category, self.query) {
+            (Some(category), Some(query)) => Some(format!("cat:{} {}", category, query)),
+            (Some(category), None) => Some(format!("cat:{}", category)),
+            (None, query) => query,
+        };
+        if let Some(query) = query {
+            params.set_query(query);
+            params.set_page(1);
+        }
+        if let Some(page) = self.page {
+            params.set_page(page);
+        }
+        if let Some(limit) = self.limit {
+            params.page_size = Some(limit);
+        }
+    }
+}
+
+fn check_page_size(size: usize) -> Result<usize, String> {
+    if (1..=MAX_PAGE_SIZE).contains(&size) {
+        Ok(size)
+    } else {
+        Err(format!(
+            "the page size must be between 1 and {}, got {}",
+            MAX_PAGE_SIZE, size
+        ))
+    }
+}
+
+// `arxiv-cli search`: one paper per line, or the raw json for scripts
+async fn print_search(params: &Params, json: bool) -> Result<(), Box<dyn Error>> {
+    let (items, _) = get_items(params, false).await?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&items)?);
+        return Ok(());
+    }
+    for item in &items {
+        let title = item.title.split_whitespace().collect::<Vec<_>>().join(" ");
+        println!("{}\t{}", strip_version(arxiv_id(&item.id)), title);
+    }
+    Ok(())
+}
+
+// recent papers in the configured home category, or everything recent without one
+fn go_home(app: &mut App, params: &mut Params) {
+    match &app.home_category {
+        Some(category) => {
+            params.set_query(format!("cat:{}", category));
+            app.set_sort(SortMode::Published);
+        }
+        None => params.set_query(""),
+    }
+    params.set_page(1);
+}
+
+// back to what a fresh start with no session shows: the default query on
+// page 1 over all fields, unsorted, with no date window or filter
+fn reset(app: &mut App, params: &mut Params) {
+    app.clear_filter();
+    app.set_sort(SortMode::default());
+    params.set_query(DEFAULT_QUERY);
+    params.after = None;
+    params.before = None;
+    params.scope = Scope::All;
+    params.set_page(1);
+}
+
+// everything that can fail on bad configuration, checked before the terminal is touched
+fn configure(app: &mut App, params: &mut Params) -> Result<(), Box<dyn Error>> {
+    let config = load_config()?;
+    // --limit wins over the config
+    if params.page_size.is_none() {
+        params.page_size = config.page_size.map(check_page_size).transpose()?;
+    }
+    app.theme = Theme::from_config(&config.theme)?;
+    app.viewer = config.viewer_command.clone();
+    app.home_category = config.home_category.clone();
+    app.show_seen = config.show_seen.unwrap_or(true);
+    app.seen_marker = config
+        .seen_marker
+        .as_deref()
+        .unwrap_or(SEEN_MARKER)
+        .to_string();
+    app.unseen_marker = config
+        .unseen_marker
+        .as_deref()
+        .unwrap_or(UNSEEN_MARKER)
+        .to_string();
+    app.wrap_around = config.wrap_around.unwrap_or(false);
+    app.auto_advance = config.auto_advance.unwrap_or(false);
+    app.confirm_quit = config.confirm_quit.unwrap_or(false);
+    params.set_client(client(timeout(&config)?, proxies(&config)?)?);
+    params.cache_ttl = cache_ttl(&config)?;
+    app.proxy = describe_proxy(&config);
+    params.set_base_url(base_url()?);
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    // handled before anything touches the terminal or the network
+    let args = match parse_args(std::env::args().skip(1)) {
+        Ok(args) if args.help => {
+            println!("{}\n\n{}", USAGE, HELP);
+            return Ok(());
+        }
+        Ok(args) if args.version => {
+            println!("arxiv-cli {}", env!("CARGO_PKG_VERSION"));
+            return Ok(());
+        }
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(2);
+        }
+    };
+    if let Err(e) = init_logging(args.log_file.clone()) {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
+    info!("arxiv-cli {} starting", env!("CARGO_PKG_VERSION"));
+    if let Err(e) = migrate_legacy_files() {
+        eprintln!(
+            "warning: couldn't move old files into the data directory: {}",
+            e
+        );
+    }
+    let mut app = App::new();
+    let mut params = Params::new();
+    let session = if args.search { None } else { load_session() };
+    // nothing to restore or search for, so start on the home view
+    let start_home = session.is_none() && args.query.is_none() && args.category.is_none();
+    if args.search && args.query.is_none() && args.category.is_none() {
+        eprintln!("search needs a query or --category\n{}", USAGE);
+        std::process::exit(2);
+    }
+    if let Some(Session {
+        query,
+        page,
+        history,
+        collection,
+        positions,
+        density,
+        scope,
+        visits,
+    }) = session
+    {
+        params.set_query(query);
+        params.set_page(page);
+        app.history = history;
+        app.positions = positions;
+        app.last_visits = visits;
+        app.set_density(density);
+        params.scope = scope;
+        if let Some(collection) = collection.filter(|name| valid_collection_name(name)) {
+            app.collection = collection;
+        }
+    }
+    let no_color = args.no_color;
+    let search = args.search.then_some(args.json);
+    args.apply(&mut params);
+    if let Err(e) = configure(&mut app, &mut params) {
+        eprintln!("error: {}", e);
+        std::process::exit(1);
+    }
+    // https://no-color.org: any non-empty value turns colors off
+    if no_color || std::env::var_os(NO_COLOR_VAR).is_some_and(|v| !v.is_empty()) {
+        app.theme = Theme::preset("no-color").expect("no-color is a preset");
+    }
+    if let Some(json) = search {
+        if let Err(e) = print_search(&params, json).await {
+            eprintln!("error: {}", describe_error(e.as_ref()));
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+    if start_home && app.home_category.is_some() {
+        go_home(&mut app, &mut params);
+    }
+    app.set_ids(get_ids(&app.collection).await);
+    app.notes = get_notes().await;
+    // bookmarks used to double as the seen column, so they start out read
+    app.read = get_read()
+        .await
+        .unwrap_or_else(|| app.ids.keys().cloned().collect());
+    // start the ui even when offline so there's a way to retry
+    match get_items(&params, false).await {
+        Ok((items, fetched)) => {
+            app.set_stale(matches!(fetched, Fetched::Stale(_)));
+            app.update_items(items);
+            app.restore_position(&params);
+            app.visit(&params.query);
+            app.shown = Some(params.clone());
+            app.prefetch_next(&params);
+        }
+        Err(e) => app.set_error(e.as_ref()),
+    }
+    if let Some(warning) = data_dir_warning() {
+        app.set_status(warning);
+    }
+    app.whats_new = is_new_version();
+
+    // leave the terminal usable if anything panics while the ui is up
+    let hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let _ = disable_raw_mode();
+        let _ = execute!(io::stdout(), LeaveAlternateScreen, DisableMouseCapture);
+        hook(info);
+    }));
+
+    // setup terminal
+    enable_raw_mode()?;
+    let mut stdout = io::stdout();
+    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
+    let backend = CrosstermBackend::new(stdout);
+    let mut terminal = Terminal::new(backend)?;
+
+    // create app and run it
+    let result = run_app(&mut terminal, app, &mut params).await;
+
+    // restore terminal, even if the app bailed out with an error
+    disable_raw_mode()?;
+    execute!(
+        terminal.backend_mut(),
+        LeaveAlternateScreen,
+        DisableMouseCapture
+    )?;
+    terminal.show_cursor()?;
+
+    result
+}
+
+// fetch the request
+async fn fetch_items(params: &Params) -> Result<Responses, Box<dyn Error>> {
+    let Params {
+        query,
+        page,
+        base_url,
+        after,
+        before,
+        scope,
+        client,
+        ..
+    } = params;
+
+    let dates = date_range(*after, *before);
+    let q = search_query(query, *scope, dates.as_deref());
+    debug!("fetching page {} of {:?} from {}", page, q, base_url);
+    let query = client
+        .get(base_url.clone())
+        .query(&[("q", &q), ("p", &page.to_string())]);
+
+    let mut attempt = 0;
+    let response = loop {
+        if let Some(wait) = params.rate_limited_for() {
+            info!("rate limited, waiting {:?}", wait);
+            tokio::time::sleep(wait).await;
+        }
+        let result = query
+            .try_clone()
+            .expect("requests without a streaming body can be cloned")
+            .send()
+            .await;
+        let retry_after = match &result {
+            Ok(response) if response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS => {
+                let wait = retry_after(response)
+                    .unwrap_or(RETRY_DELAYS[attempt.min(RETRY_DELAYS.len() - 1)]);
+                params.back_off(wait);
+                warn!("rate limited, backing off for {:?}", wait);
+                if attempt == RETRY_DELAYS.len() {
+                    return Err(RateLimited(params.rate_limited_for().unwrap_or(wait)).into());
+                }
+                attempt += 1;
+                continue;
+            }
+            Ok(response) if is_transient(response.status()) => retry_after(response),
+            Ok(_) => break result?,
+            // a timeout has already waited long enough, so only connection errors retry
+            Err(e) if e.is_connect() => None,
+            Err(_) => break result?,
+        };
+        if attempt == RETRY_DELAYS.len() {
+            break result?;
+        }
+        let delay = retry_after.unwrap_or(RETRY_DELAYS[attempt]);
+        match &result {
+            Ok(response) => warn!("got {}, retrying in {:?}", response.status(), delay),
+            Err(e) => warn!("{}, retrying in {:?}", e, delay),
+        }
+        tokio::time::sleep(delay.min(MAX_RETRY_AFTER)).await;
+        attempt += 1;
+    };
+    let body = response.error_for_status()?.text().await?;
+    let res = serde_json::from_str::<Responses>(&body).map_err(|e| {
+        let log = log_response(&body, &e);
+        error!("couldn't parse the response: {}", e);
+        UnexpectedResponse(e, log)
+    })?;
+    debug!("got {} papers", res.len());
+
+    Ok(res)
+}
+
+// still getting 429s after retrying, with how long until the next request is let through
+#[derive(Debug)]
+struct RateLimited(Duration);
+
+impl fmt::Display for RateLimited {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "rate limited by the server, try again in {}s",
+            whole_seconds(self.0)
+        )
+    }
+}
+
+impl Error for RateLimited {}
+
+// rounded up so a countdown doesn't show 0s while still waiting
+fn whole_seconds(duration: Duration) -> u64 {
+    duration.as_secs() + u64::from(duration.subsec_nanos() > 0)
+}
+
+// a body that didn't parse, along with where it was logged (if anywhere)
+#[derive(Debug)]
+struct UnexpectedResponse(serde_json::Error, Option<PathBuf>);
+
+impl fmt::Display for UnexpectedResponse {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.1 {
+            Some(log) => write!(
+                f,
+                "unexpected response from server (logged to {})",
+                log.display()
+            ),
+            None => write!(f, "unexpected response from server"),
+        }
+    }
+}
+
+impl Error for UnexpectedResponse {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.0)
+    }
+}
+
+// keep the raw body of the last response that failed to parse for debugging
+fn log_response(body: &str, error: &serde_json::Error) -> Option<PathBuf> {
+    let dir = dirs::cache_dir()?.join(CACHE_DIR);
+    std::fs::create_dir_all(&dir).ok()?;
+    let path = dir.join(RESPONSE_LOG);
+    std::fs::write(&path, format!("{}\n\n{}", error, body)).ok()?;
+    Some(path)
+}
+
+fn describe_error(e: &(dyn Error + 'static)) -> String {
+    match e.downcast_ref::<reqwest::Error>() {
+        Some(e) if e.is_timeout() => "the request timed out".to_string(),
+        _ => e.to_string(),
+    }
+}
+
+// 5xx errors and rate limiting are worth retrying, other 4xx errors are not
+fn is_transient(status: reqwest::StatusCode) -> bool {
+    status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS
+}
+
+// only the delay-seconds form of Retry-After is supported
+fn retry_after(response: &reqwest::Response) -> Option<Duration> {
+    response
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+        .map(Duration::from_secs)
+}
+
+// where a page of results came from
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Fetched {
+    Network,
+    Cache,
+    // the network failed, so this is an expired cache entry of the given age
+    Stale(Duration),
+}
+
+fn cache_ttl(config: &Config) -> Result<Duration, Box<dyn Error>> {
+    let seconds = match std::env::var(CACHE_TTL_VAR) {
+        Ok(seconds) => seconds
+            .parse()
+            .map_err(|_| format!("{} is not a number of seconds: {}", CACHE_TTL_VAR, seconds))?,
+        Err(_) => match config.cache_ttl {
+            Some(seconds) => seconds,
+            None => return Ok(DEFAULT_CACHE_TTL),
+        },
+    };
+    Ok(Duration::from_secs(seconds))
+}
+
+// identifies a page of results, for the cache and the prefetch
+fn page_key(params: &Params) -> u64 {
+    use std::hash::{Hash, Hasher};
+
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    (
+        params.base_url.as_str(),
+        &params.query,
+        params.page,
+        params.after,
+        params.before,
+        params.scope,
+    )
+        .hash(&mut hasher);
+    hasher.finish()
+}
+
+fn cache_path(params: &Params) -> Option<PathBuf> {
+    dirs::cache_dir().map(|cache| {
+        cache
+            .join(CACHE_DIR)
+            .join(format!("{:016x}.json", page_key(params)))
+    })
+}
+
+fn read_cache(params: &Params) -> Option<(Responses, Duration)> {
+    let path = cache_path(params)?;
+    let age = std::fs::metadata(&path)
+        .ok()?
+        .modified()
+        .ok()?
+        .elapsed()
+        .ok()?;
+    let items = serde_json::from_str(&std::fs::read_to_string(path).ok()?).ok()?;
+    Some((items, age))
+}
+
+fn write_cache(params: &Params, items: &Responses) -> Result<(), Box<dyn Error>> {
+    if let Some(path) = cache_path(params) {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(&path, serde_json::to_string(items)?)?;
+        if let Some(dir) = path.parent() {
+            prune_cache(dir, params.cache_ttl.max(MAX_CACHE_AGE));
+        }
+    }
+    Ok(())
+}
+
+// removes cached pages older than `max_age`, so the cache doesn't grow forever
+fn prune_cache(dir: &std::path::Path, max_age: Duration) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().is_none_or(|extension| extension != "json") {
+            continue;
+        }
+        let expired = entry
+            .metadata()
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .and_then(|modified| modified.elapsed().ok())
+            .is_some_and(|age| age > max_age);
+        if expired {
+            if let Err(e) = std::fs::remove_file(&path) {
+                debug!("couldn't prune {}: {}", path.display(), e);
+            }
+        }
+    }
+}
+
+// serve pages from the cache while they're fresh, falling back to an expired
+// entry when the network is down. `refresh` skips straight to the network.
+async fn get_items(params: &Params, refresh: bool) -> Result<(Responses, Fetched), Box<dyn Error>> {
+    let (mut items, fetched) = get_page(params, refresh).await?;
+    if let Some(size) = params.page_size {
+        items.truncate(size);
+    }
+    Ok((items, fetched))
+}
+
+// the whole page as the backend sent it, which is also what gets cached
+async fn get_page(params: &Params, refresh: bool) -> Result<(Responses, Fetched), Box<dyn Error>> {
+    if !refresh {
+        if let Some((items, age)) = read_cache(params) {
+            if age < params.cache_ttl {
+                return Ok((items, Fetched::Cache));
+            }
+        }
+    }
+
+    match fetch_items(params).await {
+        Ok(items) => {
+            // a cache that can't be written shouldn't stop the page from showing
+            let _ = write_cache(params, &items);
+            Ok((items, Fetched::Network))
+        }
+        Err(e) => match read_cache(params) {
+            Some((items, age)) => Ok((items, Fetched::Stale(age))),
+            None => Err(e),
+        },
+    }
+}
+
+// look up a single paper by its bare arXiv id
+async fn get_paper(params: &Params, id: &str) -> Result<Option<Response>, Box<dyn Error>> {
+    let mut lookup = params.clone();
+    lookup.set_query(format!("id:{}", id));
+    lookup.page = 1;
+    lookup.after = None;
+    lookup.before = None;
+    lookup.scope = Scope::All;
+    let items = fetch_items(&lookup).await?;
+    Ok(items.into_iter().next())
+}
+
+// the vim-style count typed before a key, like the 5 in `5j`
+#[derive(Debug, Default)]
+struct Count(Option<usize>);
+
+impl Count {
+    fn push(&mut self, digit: char) {
+        let digit = digit.to_digit(10).unwrap_or(0) as usize;
+        self.0 = Some(self.0.unwrap_or(0).saturating_mul(10).saturating_add(digit));
+    }
+
+    fn take(&mut self) -> Option<usize> {
+        self.0.take()
+    }
+
+    // a count as a number of pages, defaulting to one
+    fn pages(count: Option<usize>) -> i64 {
+        count.map_or(1, |n| i64::try_from(n).unwrap_or(i64::MAX))
+    }
+}
+
+// raw mode turns ctrl-c into an ordinary key press instead of a signal
+fn is_interrupt(key: &KeyEvent) -> bool {
+    key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL)
+}
+
+// waits for a key press, redrawing whenever the terminal is resized
+fn read_key<B: Backend>(
+    terminal: &mut Terminal<B>,
+    mut draw: impl FnMut(&mut Frame<B>),
+) -> io::Result<KeyEvent> {
+    loop {
+        terminal.draw(|f| draw(f))?;
+        if let Event::Key(key) = event::read()? {
+            return Ok(key);
+        }
+    }
+}
+
+// read a line of input, returning None if it was cancelled with Esc.
+// up and down walk back and forth through `history` like a shell.
+// the prompt sits over whatever `background` draws, so the results stay
+// visible while typing. nothing is fetched until the caller gets the line.
+fn read_line<B: Backend>(
+    terminal: &mut Terminal<B>,
+    mut background: impl FnMut(&mut Frame<B>),
+    prompt: &str,
+    initial: &str,
+    history: &[String],
+) -> io::Result<Option<String>> {
+    let mut line = initial.to_string();
+    let mut draft = String::from("");
+    let mut position = history.len();
+    loop {
+        terminal.draw(|f| {
+            background(f);
+            prompt_ui(f, prompt, &line);
+        })?;
+        if let Event::Key(key) = event::read()? {
+            if is_interrupt(&key) {
+                return Ok(None);
+            }
+            match key.code {
+                KeyCode::Char(c) => line.push(c),
+                KeyCode::Up if position > 0 => {
+                    if position == history.len() {
+                        draft = line.clone();
+                    }
+                    position -= 1;
+                    line = history[position].clone();
+                }
+                KeyCode::Down if position < history.len() => {
+                    position += 1;
+                    line = history
+                        .get(position)
+                        .cloned()
+                        .unwrap_or_else(|| draft.clone());
+                }
+                KeyCode::Enter => return Ok(Some(line)),
+                KeyCode::Esc => return Ok(None),
+                KeyCode::Backspace => {
+                    line.pop();
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+// a popup list of `rows` over `background`, starting on `selected` and
+// returning the index picked with Enter
+fn choose<B: Backend>(
+    terminal: &mut Terminal<B>,
+    mut background: impl FnMut(&mut Frame<B>),
+    theme: &Theme,
+    title: &str,
+    rows: &[Vec<String>],
+    widths: &[Constraint],
+    selected: usize,
+) -> io::Result<Option<usize>> {
+    let last = rows.len().saturating_sub(1);
+    let mut state = TableState::default();
+    state.select(Some(selected.min(last)));
+    loop {
+        let key = read_key(terminal, |f| {
+            background(f);
+            chooser_ui(f, theme, title, rows, widths, &mut state);
+        })?;
+        if is_interrupt(&key) {
+            return Ok(None);
+        }
+        let selected = state.selected().unwrap_or(0);
+        match key.code {
+            KeyCode::Down | KeyCode::Char('j') => state.select(Some((selected + 1).min(last))),
+            KeyCode::Up | KeyCode::Char('k') => state.select(Some(selected.saturating_sub(1))),
+            KeyCode::Char('g') | KeyCode::Home => state.select(Some(0)),
+            KeyCode::Char('G') | KeyCode::End => state.select(Some(last)),
+            KeyCode::Enter => return Ok(Some(selected)),
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(None),
+            _ => {}
+        }
+    }
+}
+
+fn show_detail<B: Backend>(
+    terminal: &mut Terminal<B>,
+    theme: &Theme,
+    item: &Response,
+    note: Option<&str>,
+) -> io::Result<()> {
+    let mut scroll: u16 = 0;
+    loop {
+        terminal.draw(|f| detail_ui(f, theme, item, note, scroll))?;
+        if let Event::Key(key) = event::read()? {
+            if is_interrupt(&key) {
+                return Ok(());
+            }
+            match key.code {
+                KeyCode::Down | KeyCode::Char('j') => scroll = scroll.saturating_add(1),
+                KeyCode::Up | KeyCode::Char('k') => scroll = scroll.saturating_sub(1),
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                _ => {}
+            }
+        }
+    }
+}
+
+// every bookmark, newest first, whether or not it's in the current results
+fn bookmarks(app: &App) -> Vec<(String, Option<Response>)> {
+    let mut entries: Vec<_> = app
+        .ids
+        .iter()
+        .map(|(id, item)| (id.clone(), item.clone()))
+        .collect();
+    entries.sort_by(|(a_id, a), (b_id, b)| {
+        let a = a.as_ref().map(|item| item.updated.as_str());
+        let b = b.as_ref().map(|item| item.updated.as_str());
+        b.cmp(&a).then_with(|| a_id.cmp(b_id))
+    });
+    entries
+}
+
+#[derive(Clone, Copy)]
+enum Bulk {
+    Open,
+    Download,
+}
+
+impl Bulk {
+    fn verb(self) -> &'static str {
+        match self {
+            Bulk::Open => "open",
+            Bulk::Download => "download",
+        }
+    }
+}
+
+// runs a bulk action over every bookmark we still have the paper for, returning the status line
+async fn bulk_pdfs<B: Backend>(
+    terminal: &mut Terminal<B>,
+    app: &App,
+    client: &reqwest::Client,
+    entries: &[(String, Option<Response>)],
+    state: &mut TableState,
+    bulk: Bulk,
+) -> io::Result<String> {
+    let items: Vec<_> = entries
+        .iter()
+        .filter_map(|(_, item)| item.as_ref())
+        .collect();
+    let mut done = 0;
+    let mut failed = 0;
+    for (i, item) in items.iter().enumerate() {
+        let result: Result<(), Box<dyn Error>> = match bulk {
+            Bulk::Open => match pdf_link(item) {
+                Some(link) => open_url(app.viewer.as_deref(), &link.href).map_err(|e| e.into()),
+                None => Err("this paper has no pdf link".into()),
+            },
+            Bulk::Download => {
+                let status = format!("downloading {}/{}…", i + 1, items.len());
+                terminal.draw(|f| {
+                    let total = app.ids.len();
+                    bookmarks_ui(
+                        f,
+                        &app.theme,
+                        &app.collection,
+                        entries,
+                        total,
+                        state,
+                        &status,
+                    )
+                })?;
+                download_pdf(client, item).await.map(|_| ())
+            }
+        };
+        match result {
+            Ok(()) => done += 1,
+            Err(_) => failed += 1,
+        }
+    }
+    let skipped = entries.len() - items.len();
+    let mut status = match bulk {
+        Bulk::Open => format!("opened {} pdfs", done),
+        Bulk::Download => match download_dir() {
+            Some(dir) => format!("downloaded {} pdfs to {}", done, dir.display()),
+            None => format!("downloaded {} pdfs", done),
+        },
+    };
+    if failed > 0 {
+        status.push_str(&format!(", {} failed", failed));
+    }
+    // bookmarks saved before papers were kept only have an id
+    if skipped > 0 {
+        status.push_str(&format!(", {} skipped without details", skipped));
+    }
+    Ok(status)
+}
+
+// bookmarks whose title, authors or note fuzzy match `filter`, all of them when it's empty
+fn filtered_bookmarks(app: &App, filter: &str) -> Vec<(String, Option<Response>)> {
+    let mut entries = bookmarks(app);
+    if !filter.is_empty() {
+        entries.retain(|(id, item)| {
+            let mut text = arxiv_id(id).to_string();
+            if let Some(item) = item {
+                let authors: Vec<_> = item.authors.iter().flatten().map(|x| x.as_str()).collect();
+                text = format!("{} {} {}", text, item.title, authors.join(" "));
+            }
+            if let Some(note) = app.note(id) {
+                text = format!("{} {}", text, note);
+            }
+            fuzzy_match(filter, &text)
+        });
+    }
+    entries
+}
+
+async fn show_bookmarks<B: Backend>(
+    terminal: &mut Terminal<B>,
+    app: &mut App,
+    client: &reqwest::Client,
+) -> io::Result<()> {
+    let mut entries = bookmarks(app);
+    let mut state = TableState::default();
+    state.select(Some(0));
+    let mut status = String::new();
+    let mut pending: Option<Bulk> = None;
+    let mut filter = String::new();
+    loop {
+        let total = app.ids.len();
+        let key = read_key(terminal, |f| {
+            bookmarks_ui(
+                f,
+                &app.theme,
+                &app.collection,
+                &entries,
+                total,
+                &mut state,
+                &status,
+            )
+        })?;
+        let selected = state.selected().unwrap_or(0);
+        if is_interrupt(&key) {
+            break;
+        }
+        if let Some(bulk) = pending.take() {
+            status = if key.code == KeyCode::Char('y') {
+                bulk_pdfs(terminal, app, client, &entries, &mut state, bulk).await?
+            } else {
+                "cancelled".to_string()
+            };
+            continue;
+        }
+        match key.code {
+            KeyCode::Char('O') | KeyCode::Char('D') => {
+                let bulk = if key.code == KeyCode::Char('O') {
+                    Bulk::Open
+                } else {
+                    Bulk::Download
+                };
+                if entries.len() > BULK_CONFIRM {
+                    status = format!(
+                        "{} all {} pdfs? y to confirm, any other key cancels",
+                        bulk.verb(),
+                        entries.len()
+                    );
+                    pending = Some(bulk);
+                } else {
+                    status = bulk_pdfs(terminal, app, client, &entries, &mut state, bulk).await?;
+                }
+            }
+            KeyCode::Char('/') => {
+                // narrows as you type, like `,` on the results
+                loop {
+                    let total = app.ids.len();
+                    status = format!(
+                        "/{}  ({} of {} match, Enter keeps, Esc clears)",
+                        filter,
+                        entries.len(),
+                        total
+                    );
+                    let key = read_key(terminal, |f| {
+                        bookmarks_ui(
+                            f,
+                            &app.theme,
+                            &app.collection,
+                            &entries,
+                            total,
+                            &mut state,
+                            &status,
+                        )
+                    })?;
+                    if is_interrupt(&key) || key.code == KeyCode::Esc {
+                        filter.clear();
+                        entries = filtered_bookmarks(app, &filter);
+                        break;
+                    }
+                    match key.code {
+                        KeyCode::Char(c) => filter.push(c),
+                        KeyCode::Backspace => {
+                            filter.pop();
+                        }
+                        KeyCode::Enter => break,
+                        _ => continue,
+                    }
+                    entries = filtered_bookmarks(app, &filter);
+                    state.select(Some(0));
+                }
+                status = String::new();
+            }
+            KeyCode::Esc if !filter.is_empty() => {
+                filter.clear();
+                entries = filtered_bookmarks(app, &filter);
+                state.select(Some(0));
+                status = String::new();
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                state.select(Some((selected + 1).min(entries.len().saturating_sub(1))));
+            }
+            KeyCode::Up | KeyCode::Char('k') => state.select(Some(selected.saturating_sub(1))),
+            KeyCode::Char('o') => {
+                if let Some((id, item)) = entries.get(selected) {
+                    let url = item.as_ref().map(abstract_url).unwrap_or(id);
+                    status = match open_url(app.viewer.as_deref(), url) {
+                        Ok(()) => format!("opened {}", url),
+                        Err(e) => e.to_string(),
+                    };
+                }
+            }
+            KeyCode::Enter => {
+                if let Some((id, Some(item))) = entries.get(selected) {
+                    show_detail(terminal, &app.theme, &app.display_item(item), app.note(id))?;
+                }
+            }
+            KeyCode::Char('d') | KeyCode::Char('r') | KeyCode::Delete
+                if selected < entries.len() =>
+            {
+                let (id, _) = entries.remove(selected);
+                app.remove_id(id);
+                state.select(Some(selected.min(entries.len().saturating_sub(1))));
+                status = "removed bookmark".to_string();
+            }
+            KeyCode::Char('q') | KeyCode::Esc => break,
+            _ => {}
+        }
+    }
+    // the saved-only view shouldn't keep showing papers that were just removed
+    if app.saved_only {
+        let ids = &app.ids;
+        app.items.retain(|item| ids.contains_key(&item.id));
+        app.select_clamped(app.current);
+    }
+    Ok(())
+}
+
+fn bookmarks_ui<B: Backend>(
+    f: &mut Frame<B>,
+    theme: &Theme,
+    collection: &str,
+    entries: &[(String, Option<Response>)],
+    total: usize,
+    state: &mut TableState,
+    status: &str,
+) {
+    let rects = Layout::default()
+        .constraints([Constraint::Min(0), Constraint::Length(1)].as_ref())
+        .split(f.size());
+
+    let rows = entries.iter().map(|(id, item)| {
+        let (title, authors, updated) = match item {
+            Some(item) => {
+                let authors: Vec<_> = item.authors.iter().flatten().map(|x| x.as_str()).collect();
+                (
+                    item.title.split_whitespace().collect::<Vec<_>>().join(" "),
+                    authors.join(", "),
+                    item.updated.clone(),
+                )
+            }
+            None => (arxiv_id(id).to_string(), String::new(), String::new()),
+        };
+        Row::new(vec![
+            Cell::from(title),
+            Cell::from(authors),
+            Cell::from(updated),
+        ])
+    });
+    let header = Row::new(
+        ["Title", "Authors", "Updated"]
+            .iter()
+            .map(|h| Cell::from(*h).style(Style::default().fg(theme.header_fg))),
+    )
+    .bottom_margin(1);
+    let table = Table::new(rows)
+        .header(header)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.border))
+                .title(if entries.len() < total {
+                    format!(
+                        "bookmarks — {} — {} of {} match",
+                        collection,
+                        entries.len(),
+                        total
+                    )
+                } else {
+                    format!("bookmarks — {} — {} saved", collection, total)
+                }),
+        )
+        .highlight_style(theme.selected_style())
+        .highlight_symbol(">> ")
+        .widths(&[
+            Constraint::Percentage(55),
+            Constraint::Percentage(30),
+            Constraint::Percentage(15),
+        ]);
+    f.render_stateful_widget(table, rects[0], state);
+
+    let footer = if status.is_empty() {
+        "j/k move  / filter  o open  O open all pdfs  D download all  Enter details  d delete  q back"
+    } else {
+        status
+    };
+    f.render_widget(Paragraph::new(footer), rects[1]);
+}
+
+// draw the loading banner before blocking on the request so the ui doesn't look frozen
+async fn load_items<B: Backend>(
+    terminal: &mut Terminal<B>,
+    app: &mut App,
+    params: &mut Params,
+    refresh: bool,
+) -> Result<(), Box<dyn Error>> {
+    app.set_loading(true);
+    terminal.draw(|f| ui(f, app, params))?;
+    app.remember_position();
+    let prefetch = app.take_prefetch(params);
+    let items = cancellable(
+        get_items_or_prefetched(prefetch, params, refresh),
+        params,
+        || terminal.draw(|f| ui(f, app, params)).map(|_| ()),
+    )
+    .await?;
+    app.set_loading(false);
+    // go back to the query and page that are still on screen
+    let Some(items) = items else {
+        if let Some(shown) = &app.shown {
+            *params = shown.clone();
+        }
+        app.set_status("cancelled");
+        terminal.draw(|f| ui(f, app, params))?;
+        return Ok(());
+    };
+    // keep the previous page on screen rather than tearing down the tui
+    match items {
+        Ok((items, fetched)) => {
+            match fetched {
+                Fetched::Stale(age) => app.set_status(format!(
+                    "offline: showing a cached copy from {} minutes ago",
+                    age.as_secs() / 60
+                )),
+                _ => app.set_status(""),
+            }
+            app.set_stale(matches!(fetched, Fetched::Stale(_)));
+            app.clear_error();
+            app.update_items(items);
+            app.restore_position(params);
+            app.visit(&params.query);
+            app.shown = Some(params.clone());
+            app.prefetch_next(params);
+        }
+        Err(e) => {
+            app.set_status("");
+            app.set_error(e.as_ref());
+        }
+    }
+    terminal.draw(|f| ui(f, app, params))?;
+    Ok(())
+}
+
+// uses the prefetched page when there is one, falling back to a normal fetch
+async fn get_items_or_prefetched(
+    prefetch: Option<Prefetch>,
+    params: &Params,
+    refresh: bool,
+) -> Result<(Responses, Fetched), Box<dyn Error>> {
+    if let Some(prefetch) = prefetch.filter(|_| !refresh) {
+        if let Ok(Some(fetched)) = prefetch.handle.await {
+            return Ok(fetched);
+        }
+    }
+    get_items(params, refresh).await
+}
+
+// runs `future` until it finishes or Esc is pressed, in which case it's dropped
+// (aborting the request) and None is returned. other keys pressed meanwhile are discarded.
+// `redraw` is called whenever the rate limit countdown in `params` changes.
+async fn cancellable<T>(
+    future: impl std::future::Future<Output = T>,
+    params: &Params,
+    mut redraw: impl FnMut() -> io::Result<()>,
+) -> io::Result<Option<T>> {
+    let cancelled = async {
+        let mut countdown = None;
+        loop {
+            let wait = params.rate_limited_for().map(whole_seconds);
+            if wait != countdown {
+                countdown = wait;
+                redraw()?;
+            }
+            while event::poll(Duration::ZERO)? {
+                if let Event::Key(key) = event::read()? {
+                    if key.code == KeyCode::Esc || is_interrupt(&key) {
+                        return io::Result::Ok(());
+                    }
+                }
+            }
+            tokio::time::sleep(CANCEL_POLL).await;
+        }
+    };
+    tokio::select! {
+        value = future => Ok(Some(value)),
+        cancelled = cancelled => cancelled.map(|_| None),
+    }
+}
+
+// moves to `page`, saying so in the status line when it's past either end
+async fn change_page<B: Backend>(
+    terminal: &mut Terminal<B>,
+    app: &mut App,
+    params: &mut Params,
+    page: i64,
+) -> Result<(), Box<dyn Error>> {
+    let target = page.clamp(1, MAX_PAGE as i64) as u16;
+    let bound = match page {
+        _ if target == params.page && page < 1 => Some("already on the first page".to_string()),
+        _ if target == params.page && page > MAX_PAGE as i64 => {
+            Some(format!("already on the last page ({})", MAX_PAGE))
+        }
+        page if page < 1 => Some("stopped at the first page".to_string()),
+        page if page > MAX_PAGE as i64 => Some(format!("stopped at the last page ({})", MAX_PAGE)),
+        _ => None,
+    };
+    if target != params.page {
+        params.set_page(target);
+        load_items(terminal, app, params, false).await?;
+    }
+    // a network error from the load is more important
+    if let Some(bound) = bound.filter(|_| app.last_error.is_none()) {
+        app.set_status(bound);
+    }
+    Ok(())
+}
+
+// re-runs the current search past the cache, keeping the selection, and
+// says how many papers weren't there before
+async fn refresh<B: Backend>(
+    terminal: &mut Terminal<B>,
+    app: &mut App,
+    params: &mut Params,
+) -> Result<(), Box<dyn Error>> {
+    let before: HashSet<String> = app.items.iter().map(|item| item.id.clone()).collect();
+    load_items(terminal, app, params, true).await?;
+    if app.last_error.is_some() || app.stale || !app.status.is_empty() {
+        return Ok(());
+    }
+    let new = app
+        .items
+        .iter()
+        .filter(|item| !before.contains(&item.id))
+        .count();
+    match new {
+        0 => app.set_status("refreshed, nothing new"),
+        1 => app.set_status("refreshed, 1 new paper"),
+        n => app.set_status(format!("refreshed, {} new papers", n)),
+    }
+    Ok(())
+}
+
+// bookmarks are saved as they're made, so this only guards against a stray `q`
+fn confirm_quit<B: Backend>(
+    terminal: &mut Terminal<B>,
+    app: &mut App,
+    params: &Params,
+) -> io::Result<bool> {
+    if !app.confirm_quit || app.added == 0 {
+        return Ok(true);
+    }
+    app.set_status(format!(
+        "{} new bookmarks this session (saved) — quit? (y/n)",
+        app.added
+    ));
+    let key = read_key(terminal, |f| ui(f, app, params))?;
+    app.set_status("");
+    Ok(matches!(key.code, KeyCode::Char('y') | KeyCode::Char('Y')))
+}
+
+// fetches the page after the last one shown and adds it to the list
+async fn load_more_items<B: Backend>(
+    terminal: &mut Terminal<B>,
+    app: &mut App,
+    params: &mut Params,
+) -> Result<(), Box<dyn Error>> {
+    let mut next = params.clone();
+    next.next_page_by(1);
+    if next.page == params.page {
+        app.exhausted = true;
+        return Ok(());
+    }
+    app.set_loading(true);
+    terminal.draw(|f| ui(f, app, params))?;
+    let prefetch = app.take_prefetch(&next);
+    let Some(items) = cancellable(
+        get_items_or_prefetched(prefetch, &next, false),
+        params,
+        || terminal.draw(|f| ui(f, app, params)).map(|_| ()),
+    )
+    .await?
+    else {
+        app.set_loading(false);
+        app.exhausted = true;
+        app.set_status("cancelled (press A twice to resume)");
+        return Ok(());
+    };
+    app.set_loading(false);
+    match items {
+        Ok((items, _)) if items.is_empty() => {
+            app.exhausted = true;
+            app.set_status("no more results");
+        }
+        Ok((items, _)) => {
+            params.set_page(next.page);
+            app.clear_error();
+            app.append_items(items);
+            app.shown = Some(params.clone());
+            app.prefetch_next(params);
+        }
+        Err(e) => {
+            app.exhausted = true;
+            app.set_error(e.as_ref());
+        }
+    }
+    Ok(())
+}
+
+async fn run_app<B: Backend>(
+    terminal: &mut Terminal<B>,
+    mut app: App,
+    params: &mut Params,
+) -> Result<(), Box<dyn Error>> {
+    let mut count = Count::default();
+    // kept alive for the whole session, since on x11 the clipboard contents
+    // are served by the process that owns them
+    let mut clipboard = arboard::Clipboard::new().ok();
+    let mut last_click: Option<(usize, Instant)> = None;
+    loop {
+        if app.wants_more_items() {
+            load_more_items(terminal, &mut app, params).await?;
+        }
+        terminal.draw(|f| ui(f, &mut app, params))?;
+        let event = event::read()?;
+        if let Event::Resize(..) = event {
+            // clears the screen so the next draw reflows at the new size
+            terminal.autoresize()?;
+        }
+        if let Event::Mouse(mouse) = event {
+            match mouse.kind {
+                MouseEventKind::ScrollDown => app.next_by(1),
+                MouseEventKind::ScrollUp => app.previous_by(1),
+                MouseEventKind::Down(MouseButton::Left) => {
+                    if let Some(i) = app.row_at(mouse.column, mouse.row) {
+                        let double_click = last_click
+                            .map(|(row, at)| row == i && at.elapsed() < DOUBLE_CLICK)
+                            .unwrap_or(false);
+                        app.select(i);
+                        if double_click {
+                            let item = &app.items[i];
+                            show_detail(
+                                terminal,
+                                &app.theme,
+                                &app.display_item(item),
+                                app.note(&item.id),
+                            )?;
+                            last_click = None;
+                        } else {
+                            last_click = Some((i, Instant::now()));
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        if let Event::Key(key) = event {
+            debug!("key {:?} {:?}", key.code, key.modifiers);
+            // any key closes the what's new notice, and still does what it normally would
+            if app.whats_new {
+                app.whats_new = false;
+                if key.code == KeyCode::Esc {
+                    continue;
+                }
+            }
+            if is_interrupt(&key) {
+                save_state(params, &app)?;
+                return Ok(());
+            }
+            // digits build up a count for the next key, which always consumes it
+            if let KeyCode::Char(c @ '0'..='9') = key.code {
+                count.push(c);
+                continue;
+            }
+            let count = count.take();
+            match key.code {
+                // vim's half and full screen motions
+                KeyCode::Char('d')
+                | KeyCode::Char('u')
+                | KeyCode::Char('f')
+                | KeyCode::Char('b')
+                    if key.modifiers.contains(KeyModifiers::CONTROL) =>
+                {
+                    let rows = app.visible_rows();
+                    let (amount, down) = match key.code {
+                        KeyCode::Char('d') => ((rows / 2).max(1), true),
+                        KeyCode::Char('u') => ((rows / 2).max(1), false),
+                        KeyCode::Char('f') => (rows, true),
+                        _ => (rows, false),
+                    };
+                    let amount = amount.saturating_mul(count.unwrap_or(1));
+                    if down {
+                        app.next_by(amount);
+                    } else {
+                        app.previous_by(amount);
+                    }
+                }
+                KeyCode::Char('q') => {
+                    let quit = confirm_quit(terminal, &mut app, params)?;
+                    if quit {
+                        save_state(params, &app)?;
+                        return Ok(());
+                    }
+                }
+                KeyCode::Char(':') => {
+                    if let Some(line) =
+                        read_line(terminal, |f| ui(f, &mut app, params), ":", "", &[])?
+                    {
+                        match parse_action(&line) {
+                            Ok(Action::Page(page)) => {
+                                change_page(terminal, &mut app, params, page as i64).await?;
+                            }
+                            Ok(Action::Open) => app.open_selected_pdf(),
+                            Ok(Action::Save) => app.save_selected(),
+                            Ok(Action::Sort(sort)) => app.set_sort(sort),
+                            Ok(Action::Scope(scope)) => {
+                                params.set_scope(scope);
+                                load_items(terminal, &mut app, params, false).await?;
+                            }
+                            Ok(Action::After(after)) => {
+                                match params.set_dates(after, params.before) {
+                                    Ok(()) => load_items(terminal, &mut app, params, false).await?,
+                                    Err(e) => app.set_status(e),
+                                }
+                            }
+                            Ok(Action::Before(before)) => {
+                                match params.set_dates(params.after, before) {
+                                    Ok(()) => load_items(terminal, &mut app, params, false).await?,
+                                    Err(e) => app.set_status(e),
+                                }
+                            }
+                            Ok(Action::Quit) => {
+                                if confirm_quit(terminal, &mut app, params)? {
+                                    save_state(params, &app)?;
+                                    return Ok(());
+                                }
+                            }
+                            Err(e) => app.set_status(e),
+                        }
+                    }
+                }
+                // infinite scroll already fetches more at the end
+                KeyCode::Down | KeyCode::Char('j')
+                    if app.auto_advance && !app.append && app.at_page_end(params) =>
+                {
+                    let page = params.page;
+                    change_page(terminal, &mut app, params, page as i64 + 1).await?;
+                    // a failed or cancelled load leaves the old page, and the selection, alone
+                    if app.shown.as_ref().is_some_and(|shown| shown.page != page) {
+                        app.first_item();
+                    }
+                }
+                KeyCode::Down | KeyCode::Char('j') => app.down(count.unwrap_or(1)),
+                KeyCode::Up | KeyCode::Char('k') => app.up(count.unwrap_or(1)),
+                KeyCode::Char('f') => app.toggle_saved_only(),
+                KeyCode::Char(' ') => {
+                    app.toggle_mark();
+                    app.next_by(1);
+                }
+                KeyCode::Char('u') => app.clear_marks(),
+                KeyCode::Char('L') => app.toggle_raw_latex(),
+                KeyCode::Char('A') => app.toggle_append(),
+                KeyCode::Char('v') => app.cycle_density(),
+                KeyCode::Char('V') => app.toggle_seen_column(),
+                KeyCode::Char('J') => app.toggle_auto_advance(),
+                KeyCode::Char('M') => app.read_page(),
+                KeyCode::Char('U') => app.unread_page(),
+                KeyCode::Char('F') => {
+                    let scope = params.scope.next();
+                    params.set_scope(scope);
+                    load_items(terminal, &mut app, params, false).await?;
+                    // a cancelled or failed load has already said so
+                    let loaded = app.shown.as_ref().is_some_and(|shown| shown.scope == scope);
+                    if loaded && app.last_error.is_none() {
+                        app.set_status(format!("searching {}", scope.label()));
+                    }
+                }
+                KeyCode::Char('B') => show_bookmarks(terminal, &mut app, &params.client).await?,
+                KeyCode::Char('l') | KeyCode::Char('a') => {
+                    let prompt = format!("collection ({}): ", collections().join(", "));
+                    let Some(name) =
+                        read_line(terminal, |f| ui(f, &mut app, params), &prompt, "", &[])?
+                    else {
+                        continue;
+                    };
+                    let name = name.trim().to_string();
+                    if !valid_collection_name(&name) {
+                        app.set_status("collection names can only use letters, digits, - and _");
+                    } else if key.code == KeyCode::Char('a') {
+                        app.add_to_collection(&name).await;
+                    } else if name != app.collection {
+                        if let Err(e) = app.save_ids() {
+                            app.set_status(format!("couldn't save bookmarks: {}", e));
+                            continue;
+                        }
+                        let ids = get_ids(&name).await;
+                        app.switch_collection(name, ids);
+                    }
+                }
+                KeyCode::Char('N') => {
+                    let Some(item) = app.selected().cloned() else {
+                        app.set_status(NOTHING_SELECTED);
+                        continue;
+                    };
+                    let note = app.note(&item.id).unwrap_or("").to_string();
+                    if let Some(note) =
+                        read_line(terminal, |f| ui(f, &mut app, params), "note: ", &note, &[])?
+                    {
+                        app.set_note(item, &note);
+                    }
+                }
+                KeyCode::Char(',') => {
+                    let mut filter = String::from("");
+                    app.set_filter(&filter);
+                    loop {
+                        app.set_status(format!(",{} ({} matches)", filter, app.items.len()));
+                        terminal.draw(|f| ui(f, &mut app, params))?;
+                        if let Event::Key(key) = event::read()? {
+                            if is_interrupt(&key) {
+                                app.clear_filter();
+                                break;
+                            }
+                            match key.code {
+                                KeyCode::Char(c) => filter.push(c),
+                                KeyCode::Backspace => {
+                                    filter.pop();
+                                }
+                                KeyCode::Down => app.down(1),
+                                KeyCode::Up => app.up(1),
+                                KeyCode::Enter => break,
+                                KeyCode::Esc => {
+                                    app.clear_filter();
+                                    break;
+                                }
+                                _ => {}
+                            }
+                            if matches!(key.code, KeyCode::Char(_) | KeyCode::Backspace) {
+                                app.set_filter(&filter);
+                            }
+                        }
+                    }
+                    app.set_status("");
+                }
+                KeyCode::Char('+') => app.grow_rows(),
+                KeyCode::Char('-') => app.shrink_rows(),
+                KeyCode::Char('S') => app.cycle_sort(),
+                KeyCode::Char('R') => refresh(terminal, &mut app, params).await?,
+                KeyCode::Char('G') => match count {
+                    Some(n) => app.select_clamped(Some(n.saturating_sub(1))),
+                    None => app.last_item(),
+                },
+                KeyCode::Char('g') => app.first_item(),
+                KeyCode::Char('n') => {
+                    let page = (params.page as i64).saturating_add(Count::pages(count));
+                    change_page(terminal, &mut app, params, page).await?;
+                }
+                KeyCode::Char('p') => {
+                    let page = (params.page as i64).saturating_sub(Count::pages(count));
+                    change_page(terminal, &mut app, params, page).await?;
+                }
+                KeyCode::Char('P') if count.is_some() => {
+                    change_page(terminal, &mut app, params, Count::pages(count)).await?;
+                }
+                KeyCode::Char('/') => {
+                    let history = app.history.clone();
+                    let background = |f: &mut Frame<B>| ui(f, &mut app, params);
+                    if let Some(input) = read_line(terminal, background, "/", "", &history)? {
+                        let query = sanitize_query(&input);
+                        app.push_history(&query);
+                        // pick up where this query was left, or start it from the top
+                        let page = app
+                            .positions
+                            .get(&query)
+                            .map_or(1, |position| position.page);
+                        params.set_query(&query);
+                        params.set_page(page);
+                        load_items(terminal, &mut app, params, false).await?;
+                        // say what was actually searched when the paste needed cleaning up
+                        if query != input && app.status.is_empty() {
+                            app.set_status(format!("searched for \"{}\"", query));
+                        }
+                    }
+                }
+                KeyCode::Char('C') => {
+                    // starting on the selected paper's primary category, so `C Enter` browses it
+                    let primary = app
+                        .selected()
+                        .and_then(|item| item.categories.first())
+                        .and_then(|category| {
+                            categories::all()
+                                .iter()
+                                .position(|(code, _)| *code == category.term)
+                        })
+                        .unwrap_or(0);
+                    let rows: Vec<_> = categories::all()
+                        .iter()
+                        .map(|(code, name)| vec![code.to_string(), name.to_string()])
+                        .collect();
+                    let widths = [Constraint::Percentage(25), Constraint::Percentage(75)];
+                    let title = "categories — j/k move  Enter browse  q back";
+                    let theme = app.theme.clone();
+                    let background = |f: &mut Frame<B>| ui(f, &mut app, params);
+                    let Some(i) =
+                        choose(terminal, background, &theme, title, &rows, &widths, primary)?
+                    else {
+                        continue;
+                    };
+                    // the newest papers first, like the home view
+                    params.set_query(format!("cat:{}", rows[i][0]));
+                    params.set_page(1);
+                    app.set_sort(SortMode::Published);
+                    load_items(terminal, &mut app, params, false).await?;
+                }
+                KeyCode::Char('i') => {
+                    if let Some(input) =
+                        read_line(terminal, |f| ui(f, &mut app, params), "arXiv id: ", "", &[])?
+                    {
+                        match parse_arxiv_id(&input) {
+                            Some(id) => {
+                                app.set_status(format!("looking up {}…", id));
+                                app.set_loading(true);
+                                terminal.draw(|f| ui(f, &mut app, params))?;
+                                // Esc gives up, including while waiting out a rate limit
+                                let found = cancellable(get_paper(params, &id), params, || {
+                                    terminal.draw(|f| ui(f, &mut app, params)).map(|_| ())
+                                })
+                                .await?;
+                                app.set_loading(false);
+                                let Some(found) = found else {
+                                    app.set_status("cancelled");
+                                    continue;
+                                };
+                                match found {
+                                    Ok(Some(item)) => {
+                                        app.set_status("");
+                                        show_detail(
+                                            terminal,
+                                            &app.theme,
+                                            &app.display_item(&item),
+                                            app.note(&item.id),
+                                        )?;
+                                    }
+                                    Ok(None) => app.set_status(format!("{} not found", id)),
+                                    Err(e) => app.set_status(format!(
+                                        "network error: {}",
+                                        describe_error(e.as_ref())
+                                    )),
+                                }
+                            }
+                            None => app.set_status(format!("{} is not an arXiv id", input)),
+                        }
+                    }
+                }
+                KeyCode::Char('o') => app.open_selected_pdf(),
+                KeyCode::Char('w') => {
+                    let Some(item) = app.selected() else {
+                        app.set_status(NOTHING_SELECTED);
+                        continue;
+                    };
+                    let Some(link) = item.links.iter().find(|link| link.rel == *"alternate") else {
+                        app.set_status("no abstract page for this paper");
+                        continue;
+                    };
+                    let href = link.href.clone();
+                    match open_url(app.viewer.as_deref(), &href) {
+                        Ok(()) => app.set_status(format!("opened {}", href)),
+                        Err(e) => app.set_status(e.to_string()),
+                    }
+                }
+                KeyCode::Char('t') => {
+                    let Some(item) = app.selected() else {
+                        app.set_status(NOTHING_SELECTED);
+                        continue;
+                    };
+                    let Some(html_link) = ar5iv_url(item) else {
+                        app.set_status("no HTML version for this paper");
+                        continue;
+                    };
+                    match open_url(app.viewer.as_deref(), &html_link) {
+                        Ok(()) => app.set_status(format!("opened {}", html_link)),
+                        Err(e) => app.set_status(e.to_string()),
+                    }
+                }
+                KeyCode::Char('b') => {
+                    go_home(&mut app, params);
+                    load_items(terminal, &mut app, params, false).await?;
+                }
+                KeyCode::Char('H') => {
+                    reset(&mut app, params);
+                    load_items(terminal, &mut app, params, false).await?;
+                    // don't jump back to wherever the default query was left
+                    app.first_item();
+                }
+                KeyCode::Enter => {
+                    if let Some(item) = app.selected() {
+                        show_detail(
+                            terminal,
+                            &app.theme,
+                            &app.display_item(item),
+                            app.note(&item.id),
+                        )?;
+                    }
+                }
+                KeyCode::Char('h') => show_help(terminal)?,
+                KeyCode::Char('s') => app.save_selected(),
+                KeyCode::Char('X') => app.toggle_read(),
+                KeyCode::Char('y') | KeyCode::Char('Y') => {
+                    let Some(item) = app.selected().cloned() else {
+                        app.set_status(NOTHING_SELECTED);
+                        continue;
+                    };
+                    let choice = if key.code == KeyCode::Char('Y') {
+                        KeyCode::Char('p')
+                    } else {
+                        app.set_status("copy (y) url, (i) arXiv id, (d) DOI or (p) pdf url?");
+                        read_key(terminal, |f| ui(f, &mut app, params))?.code
+                    };
+                    let text = match choice {
+                        KeyCode::Char('y') | KeyCode::Char('u') => {
+                            Ok(abstract_url(&item).to_string())
+                        }
+                        KeyCode::Char('i') => Ok(strip_version(arxiv_id(&item.id)).to_string()),
+                        KeyCode::Char('d') => doi(&item)
+                            .map(str::to_string)
+                            .ok_or("this paper has no DOI"),
+                        KeyCode::Char('p') => pdf_link(&item)
+                            .map(|link| link.href.clone())
+                            .ok_or("this paper has no pdf link"),
+                        _ => {
+                            app.set_status("");
+                            continue;
+                        }
+                    };
+                    let copied = match (text, clipboard.as_mut()) {
+                        (Ok(text), Some(clipboard)) => clipboard
+                            .set_text(text.clone())
+                            .map(|_| text)
+                            .map_err(|e| e.to_string()),
+                        (Err(e), _) => Err(e.to_string()),
+                        (_, None) => Err("no clipboard available".to_string()),
+                    };
+                    match copied {
+                        Ok(text) => app.set_status(format!("copied {}", text)),
+                        Err(e) => app.set_status(e),
+                    }
+                }
+                KeyCode::Char('E') => {
+                    let Some(item) = app.selected() else {
+                        app.set_status(NOTHING_SELECTED);
+                        continue;
+                    };
+                    let Some(url) = source_url(item) else {
+                        app.set_status("this paper has no source link");
+                        continue;
+                    };
+                    match open_url(app.viewer.as_deref(), &url) {
+                        Ok(()) => app.set_status(format!("opened {}", url)),
+                        Err(e) => app.set_status(e.to_string()),
+                    }
+                }
+                KeyCode::Char('W') => {
+                    let Some(item) = app.selected().cloned() else {
+                        app.set_status(NOTHING_SELECTED);
+                        continue;
+                    };
+                    let links = all_links(&item);
+                    if links.is_empty() {
+                        app.set_status("this paper has no links");
+                        continue;
+                    }
+                    let theme = app.theme.clone();
+                    let background = |f: &mut Frame<B>| ui(f, &mut app, params);
+                    let widths = [
+                        Constraint::Percentage(20),
+                        Constraint::Percentage(20),
+                        Constraint::Percentage(60),
+                    ];
+                    let title = "links — j/k move  Enter open  q back";
+                    let Some(i) = choose(terminal, background, &theme, title, &links, &widths, 0)?
+                    else {
+                        continue;
+                    };
+                    let url = &links[i][2];
+                    match open_url(app.viewer.as_deref(), url) {
+                        Ok(()) => app.set_status(format!("opened {}", url)),
+                        Err(e) => app.set_status(e.to_string()),
+                    }
+                }
+                KeyCode::Char('x') => {
+                    let Some(item) = app.selected().cloned() else {
+                        app.set_status(NOTHING_SELECTED);
+                        continue;
+                    };
+                    app.set_status("copy citation as (p)lain text or (m)arkdown?");
+                    let style = match read_key(terminal, |f| ui(f, &mut app, params))?.code {
+                        KeyCode::Char('p') => CitationStyle::Plain,
+                        KeyCode::Char('m') => CitationStyle::Markdown,
+                        _ => {
+                            app.set_status("");
+                            continue;
+                        }
+                    };
+                    let text = citation(&item, style);
+                    let copied = match clipboard.as_mut() {
+                        Some(clipboard) => clipboard.set_text(text).map_err(|e| e.to_string()),
+                        None => Err("no clipboard available".to_string()),
+                    };
+                    match copied {
+                        Ok(()) => app.set_status("copied citation"),
+                        Err(e) => app.set_status(e),
+                    }
+                }
+                KeyCode::Char('e') => {
+                    app.set_status("export as (j)son or (c)sv?");
+                    let extension = match read_key(terminal, |f| ui(f, &mut app, params))?.code {
+                        KeyCode::Char('j') => Some("json"),
+                        KeyCode::Char('c') => Some("csv"),
+                        _ => None,
+                    };
+                    let items = if app.marked.is_empty() {
+                        app.items.clone()
+                    } else {
+                        app.targets()
+                    };
+                    match extension.map(|extension| export_items(&items, extension)) {
+                        Some(Ok(path)) => app.set_status(format!("exported to {}", path)),
+                        Some(Err(e)) => app.set_status(format!("export failed: {}", e)),
+                        None => app.set_status(""),
+                    }
+                }
+                KeyCode::Char('c') => {
+                    if let Some(item) = app.selected() {
+                        match save_citation(item) {
+                            Ok(path) => app.set_status(format!("saved citation to {}", path)),
+                            Err(e) => app.set_status(e.to_string()),
+                        }
+                    }
+                }
+                KeyCode::Char('D') => {
+                    if let Some(item) = app.selected().cloned() {
+                        app.set_status(format!("downloading {}…", arxiv_id(&item.id)));
+                        terminal.draw(|f| ui(f, &mut app, params))?;
+                        match download_pdf(&params.client, &item).await {
+                            Ok(path) => app.set_status(format!("downloaded {}", path.display())),
+                            Err(e) => app.set_status(format!("download failed: {}", e)),
+                        }
+                    }
+                }
+                KeyCode::Char('O') => {
+                    let Some(item) = app.selected().cloned() else {
+                        app.set_status(NOTHING_SELECTED);
+                        continue;
+                    };
+                    let downloaded = download_dir()
+                        .map(|dir| dir.join(pdf_file_name(&item)))
+                        .filter(|path| path.exists());
+                    let path = match downloaded {
+                        Some(path) => Ok(path),
+                        None => {
+                            app.set_status(format!("downloading {}…", arxiv_id(&item.id)));
+                            terminal.draw(|f| ui(f, &mut app, params))?;
+                            download_pdf(&params.client, &item).await
+                        }
+                    };
+                    match path {
+                        Ok(path) => {
+                            let file = path.display().to_string();
+                            match open_url(app.viewer.as_deref(), &file) {
+                                Ok(()) => app.set_status(format!("opened {}", file)),
+                                Err(e) => app.set_status(e.to_string()),
+                            }
+                        }
+                        Err(e) => app.set_status(format!("download failed: {}", e)),
+                    }
+                }
+                KeyCode::Char('r') => {
+                    if let Some(item) = app.selected() {
+                        let id = item.id.clone();
+                        app.remove_id(id);
+                    }
+                }
+                // like `D`, but also bookmarks the paper once it's downloaded
+                KeyCode::Char('d') => {
+                    let Some(item) = app.selected().cloned() else {
+                        app.set_status(NOTHING_SELECTED);
+                        continue;
+                    };
+                    app.set_status(format!("downloading {}…", arxiv_id(&item.id)));
+                    terminal.draw(|f| ui(f, &mut app, params))?;
+                    match download_pdf(&params.client, &item).await {
+                        Ok(path) => {
+                            app.set_status(format!("downloaded and saved {}", path.display()));
+                            app.add_item(item);
+                            app.autosave();
+                        }
+                        Err(e) => app.set_status(format!("download failed: {}", e)),
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+fn chooser_ui<B: Backend>(
+    f: &mut Frame<B>,
+    theme: &Theme,
+    title: &str,
+    rows: &[Vec<String>],
+    widths: &[Constraint],
+    state: &mut TableState,
+) {
+    let size = f.size();
+    // room for the borders and one line per row, centered and scrolling past the screen
+    let width = size.width.saturating_sub(4).min(100);
+    let height = (rows.len() as u16 + 2).min(size.height.saturating_sub(2));
+    let area = Rect::new(
+        size.x + (size.width - width) / 2,
+        size.y + (size.height - height) / 2,
+        width,
+        height,
+    );
+
+    let rows = rows
+        .iter()
+        .map(|row| Row::new(row.iter().map(|cell| Cell::from(cell.as_str()))));
+    let table = Table::new(rows)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.border))
+                .title(title),
+        )
+        .highlight_style(theme.selected_style())
+        .highlight_symbol(">> ")
+        .widths(widths);
+    f.render_widget(Clear, area);
+    f.render_stateful_widget(table, area, state);
+}
+
+// a one line input box along the bottom of the screen, over the status line
+fn prompt_ui<B: Backend>(f: &mut Frame<B>, prompt: &str, text: &str) {
+    let size = f.size();
+    let height = size.height.min(3);
+    let area = Rect::new(size.x, size.y + size.height - height, size.width, height);
+
+    let input = format!("{}{}", prompt, text);
+    // keep the end of long input in view
+    let inner_width = area.width.saturating_sub(2) as usize;
+    let shown: String = match input
+        .chars()
+        .count()
+        .checked_sub(inner_width.saturating_sub(1))
+    {
+        Some(skip) if skip > 0 => input.chars().skip(skip).collect(),
+        _ => input,
+    };
+    let cursor = shown.chars().count() as u16;
+
+    let paragraph = Paragraph::new(Span::styled(shown, Style::default())).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title("enter to submit, esc to cancel"),
+    );
+    f.render_widget(Clear, area);
+    f.render_widget(paragraph, area);
+    f.set_cursor(area.x + 1 + cursor, area.y + 1);
+}
+
+// the lowercased words of a search worth highlighting in results
+fn query_terms(query: &str) -> Vec<Vec<char>> {
+    query
+        .split_whitespace()
+        .filter(|term| !term.starts_with("cat:") && !matches!(*term, "AND" | "OR" | "ANDNOT"))
+        .map(|term| {
+            let value = SEARCH_FIELDS
+                .iter()
+                .find_map(|(prefix, _)| term.strip_prefix(prefix))
+                .unwrap_or(term);
+            value
+                .trim_matches(|c: char| !c.is_alphanumeric())
+                .chars()
+                .flat_map(char::to_lowercase)
+                .collect::<Vec<_>>()
+        })
+        .filter(|term| term.len() > 1)
+        .collect()
+}
+
+// styles every case insensitive occurrence of `terms` in `text`
+fn highlight(text: &str, terms: &[Vec<char>], style: Style) -> Text<'static> {
+    let lines = text.lines().map(|line| {
+        let chars: Vec<char> = line.chars().collect();
+        // lowercasing char by char keeps indices lined up with `chars`
+        let lower: Vec<char> = chars
+            .iter()
+            .map(|c| c.to_lowercase().next().unwrap_or(*c))
+            .collect();
+        let mut matched = vec![false; chars.len()];
+        for term in terms {
+            for start in 0..lower.len().saturating_sub(term.len() - 1) {
+                if lower[start..].starts_with(term) {
+                    matched[start..start + term.len()].fill(true);
+                }
+            }
+        }
+
+        let mut spans = vec![];
+        let mut start = 0;
+        for end in 1..=chars.len() {
+            if end == chars.len() || matched[end] != matched[start] {
+                let piece: String = chars[start..end].iter().collect();
+                spans.push(if matched[start] {
+                    Span::styled(piece, style)
+                } else {
+                    Span::raw(piece)
+                });
+                start = end;
+            }
+        }
+        Spans::from(spans)
+    });
+    Text::from(lines.collect::<Vec<_>>())
+}
+
+// roughly the characters tui gives a percentage column, after the borders,
+// the highlight symbol and the one space gap between columns
+fn column_width(table_width: u16, percent: u16) -> usize {
+    (table_width.saturating_sub(2 + 3) as usize * percent as usize / 100).saturating_sub(1)
+}
+
+// the percentage that fits `columns` characters plus their gap, the inverse of column_width
+fn percent_of(table_width: u16, columns: u16) -> u16 {
+    let available = table_width.saturating_sub(2 + 3).max(1) as usize;
+    ((columns as usize + 1) * 100).div_ceil(available) as u16
+}
+
+// terminal columns rather than chars, since emoji are two wide
+fn text_width(text: &str) -> u16 {
+    unicode_width::UnicodeWidthStr::width(text) as u16
+}
+
+// word wraps text to `width` and keeps at most `lines` lines, ending in an
+// ellipsis on a word boundary when anything was cut
+fn preview(text: &str, width: usize, lines: usize) -> String {
+    let width = width.max(1);
+    let mut wrapped: Vec<String> = vec![];
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        let len = line.chars().count();
+        if len > 0 && len + 1 + word.chars().count() > width {
+            wrapped.push(std::mem::take(&mut line));
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(word);
+    }
+    if !line.is_empty() {
+        wrapped.push(line);
+    }
+
+    if wrapped.len() > lines && lines > 0 {
+        wrapped.truncate(lines);
+        let last = wrapped.last_mut().expect("lines is at least one");
+        // drop words until the ellipsis fits
+        while last.chars().count() + 1 > width {
+            match last.rfind(' ') {
+                Some(i) => last.truncate(i),
+                None => break,
+            }
+        }
+        last.push('…');
+    }
+    wrapped.join("\n")
+}
+
+fn ui<B: Backend>(f: &mut Frame<B>, app: &mut App, params: &Params) {
+    // the margin, table borders, header, one row and the footer
+    let min_height = 2 + 2 + HEADER_HEIGHT + app.row_height + 1;
+    let size = f.size();
+    if size.width < MIN_WIDTH || size.height < min_height {
+        let message = format!(
+            "terminal too small (needs at least {}x{})",
+            MIN_WIDTH, min_height
+        );
+        // wrapped over a couple of lines for very narrow panes
+        let height = size.height.min(2);
+        let middle = Rect::new(
+            size.x,
+            size.y + (size.height - height) / 2,
+            size.width,
+            height,
+        );
+        f.render_widget(
+            Paragraph::new(message)
+                .alignment(Alignment::Center)
+                .wrap(Wrap { trim: true }),
+            middle,
+        );
+        return;
+    }
+    let banner = if app.last_error.is_some() { 1 } else { 0 };
+    let rects = Layout::default()
+        .constraints(
+            [
+                Constraint::Min(0),
+                Constraint::Length(banner),
+                Constraint::Length(1),
+            ]
+            .as_ref(),
+        )
+        .margin(1)
+        .split(f.size());
+
+    let theme = &app.theme;
+    let selected_style = theme.selected_style();
+    let normal_style = Style::default().bg(theme.header_bg);
+    let border_style = Style::default().fg(theme.border);
+    let compact = app.is_compact();
+    let show_seen = app.show_seen;
+    // the marker and seen columns fit what's in them, and the title gives up that much
+    let marker_width = app
+        .items
+        .iter()
+        .map(|item| text_width(&app.markers(&item.id)))
+        .max()
+        .unwrap_or(0)
+        .max(1);
+    let seen_width = text_width(&app.seen_marker)
+        .max(text_width(&app.unseen_marker))
+        .max(1);
+    let mut given = percent_of(rects[0].width, marker_width);
+    if show_seen {
+        given += percent_of(rects[0].width, seen_width);
+    }
+    let title_percent = TITLE_PERCENT.saturating_sub(given);
+    let mut columns = if compact {
+        vec!["", "Seen", "Title", "Date"]
+    } else {
+        vec![
+            "",
+            "Seen",
+            "Title",
+            "Summary",
+            "Authors",
+            "Categories",
+            "Date",
+        ]
+    };
+    let mut widths = if compact {
+        vec![
+            Constraint::Length(marker_width),
+            Constraint::Length(seen_width),
+            Constraint::Percentage(88u16.saturating_sub(given)),
+            Constraint::Percentage(6),
+        ]
+    } else {
+        vec![
+            Constraint::Length(marker_width),
+            Constraint::Length(seen_width),
+            Constraint::Percentage(title_percent),
+            Constraint::Percentage(SUMMARY_PERCENT),
+            Constraint::Percentage(14),
+            Constraint::Percentage(8),
+            Constraint::Percentage(6),
+        ]
+    };
+    if !show_seen {
+        columns.remove(1);
+        widths.remove(1);
+    }
+    let title_width = column_width(rects[0].width, title_percent);
+    let summary_width = column_width(rects[0].width, SUMMARY_PERCENT);
+    let header_cells = columns
+        .iter()
+        .map(|h| Cell::from(*h).style(Style::default().fg(theme.header_fg)));
+    let header = Row::new(header_cells)
+        .style(normal_style)
+        .height(1)
+        .bottom_margin(1);
+    let matches = query_terms(&params.query);
+    let max_authors = app.density.max_authors();
+    let highlight_style = Style::default().add_modifier(Modifier::BOLD | Modifier::UNDERLINED);
+    let rows = app.items.iter().map(|item| {
+        let Response {
+            id,
+            updated,
+            title,
+            summary,
+            authors,
+            categories,
+            ..
+        } = item;
+        let flattened_authors: Vec<_> = authors.iter().flatten().map(|x| x.to_string()).collect();
+        // the detail view has the full list for large collaborations
+        let authors_str = match flattened_authors.len() {
+            n if n > max_authors => format!(
+                "{}, +{} more",
+                flattened_authors[..max_authors].join(", "),
+                n - max_authors
+            ),
+            _ => flattened_authors.join(", "),
+        };
+        let terms: Vec<_> = categories.iter().map(|c| c.term.as_str()).collect();
+        let categories_str = terms.join(", ");
+
+        let viewed = if app.read.contains(id) {
+            app.seen_marker.as_str()
+        } else {
+            app.unseen_marker.as_str()
+        };
+        let marked = &app.markers(id);
+        let title = &app.display_text(title);
+        // wrapped by hand since cells only break on newlines
+        let wrapped_title = preview(title, title_width, app.row_height as usize);
+        let summary = preview(
+            &app.display_text(summary),
+            summary_width,
+            app.row_height as usize,
+        );
+        let compact_title = title.split_whitespace().collect::<Vec<_>>().join(" ");
+        let badges: Vec<_> = [
+            (is_revised(item), "rev"),
+            (app.is_new(&params.query, item), "new"),
+        ]
+        .into_iter()
+        .filter_map(|(shown, badge)| shown.then_some(badge))
+        .collect();
+        let updated = &match (badges.is_empty(), compact) {
+            (true, _) => updated.to_string(),
+            (false, true) => format!("{} ({})", updated, badges.join(", ")),
+            (false, false) => format!("{}\n({})", updated, badges.join(", ")),
+        };
+        let cells = if compact {
+            vec![marked, viewed, &compact_title, updated]
+        } else {
+            vec![
+                marked,
+                viewed,
+                &wrapped_title,
+                &summary,
+                &authors_str,
+                &categories_str,
+                updated,
+            ]
+        }
+        .into_iter()
+        .enumerate()
+        .filter(|(i, _)| show_seen || *i != 1)
+        .map(|(i, x)| {
+            let is_text = i == 2 || (i == 3 && !compact);
+            let cell = if is_text {
+                Cell::from(highlight(x, &matches, highlight_style))
+            } else {
+                Cell::from(Text::from(x.to_string()))
+            };
+            if i == 1 {
+                cell.style(Style::default().fg(theme.seen_marker))
+            } else {
+                cell
+            }
+        });
+        Row::new(cells)
+            .height(app.row_height)
+            .bottom_margin(app.row_margin())
+    });
+    let query = match params.query.strip_prefix("cat:").and_then(categories::name) {
+        _ if params.query.is_empty() => "all recent".to_string(),
+        Some(name) => format!("\"{}\" ({})", params.query, name),
+        None => format!("\"{}\"", params.query),
+    };
+    // the backend returns a bare list, so there's no total to show
+    let pages = if app.appended > 0 {
+        format!("pages {}–{}", params.page - app.appended, params.page)
+    } else {
+        format!("page {}", params.page)
+    };
+    let mut title = format!(
+        "arxiv — {} — {} — showing {} results",
+        query,
+        pages,
+        app.items.len()
+    );
+    match (params.after, params.before) {
+        (Some(after), Some(before)) => title.push_str(&format!(" — {} to {}", after, before)),
+        (Some(after), None) => title.push_str(&format!(" — after {}", after)),
+        (None, Some(before)) => title.push_str(&format!(" — before {}", before)),
+        (None, None) => {}
+    }
+    let new = app
+        .items
+        .iter()
+        .filter(|item| app.is_new(&params.query, item))
+        .count();
+    if new > 0 {
+        title.push_str(&format!(" — {} new since last visit", new));
+    }
+    if app.collection != DEFAULT_COLLECTION {
+        title.push_str(&format!(" — collection {}", app.collection));
+    }
+    if params.scope != Scope::All {
+        title.push_str(&format!(" — searching {}", params.scope.label()));
+    }
+    if app.sort != SortMode::Relevance {
+        title.push_str(&format!(" — sorted by {}", app.sort.label()));
+    }
+    if app.stale {
+        title.push_str(" — offline (cached)");
+    }
+    if !app.marked.is_empty() {
+        title.push_str(&format!(" — {} marked", app.marked.len()));
+    }
+    if app.is_filtered() {
+        title.push_str(" — filtered");
+    }
+    if app.saved_only {
+        title.push_str(" — saved only");
+    }
+    if let Some(wait) = params.rate_limited_for().filter(|_| app.loading) {
+        title.push_str(&format!(
+            " — rate limited, waiting {}s… (Esc to cancel)",
+            whole_seconds(wait)
+        ));
+    } else if app.loading {
+        title.push_str(" — Loading… (Esc to cancel)");
+    }
+    if app.items.is_empty() && !app.loading {
+        let message = if app.saved_only {
+            "No saved papers — press f to go back".to_string()
+        } else if app.last_error.is_some() {
+            "Couldn't load any results — press R to retry".to_string()
+        } else {
+            format!(
+                "No results for '{}' — press / to search again",
+                params.query
+            )
+        };
+        // pad with blank lines to roughly center the message vertically
+        let mut text = vec![Spans::from(""); (rects[0].height / 2).saturating_sub(1) as usize];
+        text.push(Spans::from(message));
+        let paragraph = Paragraph::new(text)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(border_style)
+                    .title(title),
+            )
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true });
+        f.render_widget(paragraph, rects[0]);
+    } else {
+        let t = Table::new(rows)
+            .header(header)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(border_style)
+                    .title(title),
+            )
+            .highlight_style(selected_style)
+            .highlight_symbol(">> ")
+            .widths(&widths);
+        app.table_area = rects[0];
+        app.offset = table_offset(
+            app.offset,
+            app.state.selected(),
+            app.items.len(),
+            app.row_height,
+            app.row_height + app.row_margin(),
+            rects[0].height.saturating_sub(2 + HEADER_HEIGHT),
+        );
+        f.render_stateful_widget(t, rects[0], &mut app.state);
+    }
+
+    let position = match app.current {
+        Some(i) if !app.items.is_empty() => format!("{}/{}", i + 1, app.items.len()),
+        _ => format!("0/{}", app.items.len()),
+    };
+    let mut footer = Spans::from(vec![
+        Span::styled(
+            format!("{} │ {} │ ", position, FOOTER_HINTS),
+            Style::default().fg(app.theme.hint),
+        ),
+        Span::styled(app.status.as_str(), Style::default().fg(app.theme.status)),
+    ]);
+    // a nudge that there's more past the end of the page
+    if app.status.is_empty() && !app.append && app.at_page_end(params) {
+        let next = if app.auto_advance { "j" } else { "n" };
+        footer.0.push(Span::styled(
+            format!("end of page — {} for the next", next),
+            Style::default().fg(app.theme.hint),
+        ));
+    }
+    f.render_widget(Paragraph::new(footer), rects[2]);
+
+    if let Some(error) = &app.last_error {
+        let banner = format!(" {} — press R to retry", error);
+        f.render_widget(
+            Paragraph::new(banner).style(app.theme.error_style()),
+            rects[1],
+        );
+    }
+
+    if app.whats_new {
+        whats_new_ui(f, &app.theme);
+    }
+}
+
+// tucked into the bottom right corner above the footer, so the results stay usable
+fn whats_new_ui<B: Backend>(f: &mut Frame<B>, theme: &Theme) {
+    let size = f.size();
+    let width = WHATS_NEW
+        .iter()
+        .map(|line| line.chars().count() as u16 + 4)
+        .max()
+        .unwrap_or(0)
+        .min(size.width);
+    let height = (WHATS_NEW.len() as u16 + 2).min(size.height.saturating_sub(2));
+    let area = Rect::new(
+        size.x + size.width - width,
+        size.y + size.height.saturating_sub(height + 2),
+        width,
+        height,
+    );
+
+    let lines: Vec<_> = WHATS_NEW
+        .iter()
+        .map(|line| Spans::from(Span::raw(format!("• {}", line))))
+        .collect();
+    let paragraph = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.hint))
+                .title(format!(
+                    "what's new in {} — any key to close",
+                    env!("CARGO_PKG_VERSION")
+                )),
+        )
+        .wrap(Wrap { trim: true });
+    f.render_widget(Clear, area);
+    f.render_widget(paragraph, area);
+}
+
+fn detail_ui<B: Backend>(
+    f: &mut Frame<B>,
+    theme: &Theme,
+    item: &Response,
+    note: Option<&str>,
+    scroll: u16,
+) {
+    let size = f.size();
+
+    let Response {
+        id,
+        title,
+        summary,
+        authors,
+        published,
+        updated,
+        categories,
+        ..
+    } = item;
+    let flattened_authors: Vec<_> = authors.iter().flatten().map(|x| x.to_string()).collect();
+    let categories: Vec<_> = categories
+        .iter()
+        .map(|c| categories::describe(&c.term))
+        .collect();
+    let label = Style::default().fg(theme.label);
+
+    let mut text = vec![
+        Spans::from(Span::styled(
+            title.to_string(),
+            Style::default().add_modifier(Modifier::BOLD),
+        )),
+        Spans::from(""),
+        Spans::from(vec![
+            Span::styled("Authors: ", label),
+            Span::from(flattened_authors.join(", ")),
+        ]),
+        Spans::from(vec![
+            Span::styled("Submitted: ", label),
+            Span::from(describe_date(published)),
+        ]),
+        Spans::from(vec![
+            Span::styled("Updated: ", label),
+            Span::from(if is_revised(item) {
+                describe_date(updated)
+            } else {
+                "never revised".to_string()
+            }),
+        ]),
+        Spans::from(vec![
+            Span::styled("Version: ", label),
+            Span::from(describe_version(item)),
+        ]),
+        Spans::from(vec![
+            Span::styled("Links: ", label),
+            Span::from(match available_links(item).join(", ") {
+                links if links.is_empty() => "none".to_string(),
+                links => links,
+            }),
+        ]),
+        Spans::from(vec![
+            Span::styled("Categories: ", label),
+            Span::from(categories.join(", ")),
+        ]),
+        Spans::from(vec![
+            Span::styled("Abstract: ", label),
+            Span::from(reading_time(summary)),
+        ]),
+        Spans::from(""),
+        Spans::from(summary.to_string()),
+    ];
+    if let Some(note) = note {
+        text.push(Spans::from(""));
+        text.push(Spans::from(vec![
+            Span::styled("Note: ", label),
+            Span::from(note.to_string()),
+        ]));
+    }
+
+    let paragraph = Paragraph::new(text)
+        .block(Block::default().borders(Borders::ALL).title(id.as_str()))
+        .alignment(Alignment::Left)
+        .wrap(Wrap { trim: true })
+        .scroll((scroll, 0));
+    f.render_widget(paragraph, size);
+}
+
+fn show_help<B: Backend>(terminal: &mut Terminal<B>) -> io::Result<()> {
+    let mut scroll: u16 = 0;
+    loop {
+        let key = read_key(terminal, |f| popup_ui(f, scroll))?;
+        if is_interrupt(&key) {
+            return Ok(());
+        }
+        match key.code {
+            KeyCode::Down | KeyCode::Char('j') => scroll = scroll.saturating_add(1),
+            KeyCode::Up | KeyCode::Char('k') => scroll = scroll.saturating_sub(1),
+            KeyCode::Char('q') | KeyCode::Char('h') | KeyCode::Esc => return Ok(()),
+            _ => {}
+        }
+    }
+}
+
+fn popup_ui<B: Backend>(f: &mut Frame<B>, scroll: u16) {
+    let size = f.size();
 
     const HELP_TEXT: &[&str] = &[
-        "use `/` to search",
-        "use `s` to mark the current spot as viewed until",
+        "use `/` to search, typing over the current results (up / down recall earlier searches).",
+        "search terms can be scoped with `author:`, `title:`, `cat:` and `abstract:` (like `author:hinton cat:cs.LG`)",
+        "use `F` to cycle which field terms without a prefix search: all fields, titles, abstracts or authors.",
+        "use `s` to save the selected item to the bookmarks (saved items show ★, `X` marks them seen separately).",
         "use `<number> n` to go to <number> pages next (like 5n to go 5 more pages)",
         "use `<number> p` to go to <number> pages previous (like 5p to go 5 fewer pages)",
+        "use `<number> P` to jump straight to page <number> (like 42P), or `:page <number>`",
         "use `<number> j` or down arrow to go down one item.",
         "use `<number> k` or up arrow to up one item.",
+        "use `Ctrl-D` / `Ctrl-U` to move half a screen down / up, and `Ctrl-F` / `Ctrl-B` for a whole screen (a count repeats them).",
+        "use `J` to have `j` on the last item go on to the next page (or set `auto_advance` in the config).",
+        "set `wrap_around` to true in the config to have `j` at the bottom go to the top and `k` at the top go to the bottom.",
+        "use `<number> G` to jump to item <number>, or `G` / `g` for the last / first item.",
         "use `o` to open the selected item in the web browser.",
         "use `t` to open up the selected item's HTML version (if it has one).",
+        "use `w` to open the selected item's arXiv abstract page (comments, DOI and versions).",
+        "use `E` to open (usually download) the selected item's LaTeX source tarball from arXiv.",
+        "use `W` to pick any of the selected item's links (pdf, abstract, DOI, html, …) from a list and open it.",
+        "use `c` to append a BibTeX entry for the selected item to citations.bib in the data directory ($ARXIV_CLI_DATA_DIR or ~/.local/share/arxiv-cli).",
+        "use `+` and `-` to make rows taller or shorter, down to a compact one line per item.",
+        "use `V` to hide or show the seen (✅/❌) column, or set `show_seen` to false in the config to start with it hidden.",
+        "set `seen_marker` and `unseen_marker` in the config (like \"[x]\" and \"[ ]\") if the emoji don't render.",
+        "use `v` to cycle list density: compact (one line, first author), normal and detailed (more summary and authors).",
+        "use `Space` to mark items, then `s`, `o` and `e` act on all marked items (`u` clears the marks).",
+        "use `X` to mark the selected (or marked) items as seen, or unseen if they already are. seen is separate from saving with `s`, which bookmarks a paper (shown as ★).",
+        "use `e` then `j` or `c` to export the current page to a json or csv file.",
+        "use `D` to download the selected item's PDF (to $ARXIV_CLI_DOWNLOAD_DIR or ~/Downloads).",
+        "use `d` to download the selected item's PDF like `D` and also save it.",
+        "use `f` to toggle showing only saved items.",
+        "use `M` to mark every item on the page as seen, or `U` to unmark them all.",
+        "use `R` to retry or refresh the current page, skipping the page cache and keeping the selection; it says how many papers are new.",
+        "use `S` to cycle sorting by relevance, last updated, published date and title.",
+        "papers published since you last looked at a search (in an earlier session) are marked (new).",
+        "use `b` to go home: recent papers in the `home_category` from the config, or all recent papers.",
+        "use `H` to reset everything: the default `algorithms` search on page 1, no sort, dates or filter.",
+        "use `C` to pick a category to browse its newest papers, starting on the selected item's primary category.",
+        "use `q` to quit; set `confirm_quit` to true in the config to be asked first when the session added bookmarks.",
+        "use `:` to run a command: `:page <n>`, `:open`, `:save`, `:sort <date|published|title|relevance>`, `:scope <all|title|abstract|author>`, `:after <YYYY-MM-DD>`, `:before <YYYY-MM-DD>` (no date clears it), `:quit`",
+        "use `i` to look up a paper by its arXiv id or url.",
+        "use `y` then `y`, `i`, `d` or `p` to copy the selected item's abstract url, bare arXiv id, DOI or pdf url (`Y` copies the pdf url straight away).",
+        "use `l` to switch to (or create) a named collection of bookmarks, and `a` to add the selected item to one without switching.",
+        "use `B` to manage every bookmark, even ones not in the current results (`d` deletes, `o` opens, `/` filters by title, author or note, `O` / `D` open or download every pdf shown, asking first past 10).",
+        "use `x` to copy a citation for the selected item as plain text or a markdown link.",
+        "click a row to select it, double click to read it, and scroll with the mouse wheel.",
+        "use `O` to open the selected item's downloaded PDF (downloading it first) in the configured viewer.",
+        "use `,` to filter the current page by title and authors as you type (`Enter` keeps it, `Esc` clears it).",
+        "use `L` to switch between prettified and raw LaTeX in titles and abstracts.",
+        "use `A` to toggle infinite scroll, which appends the next page when you reach the bottom.",
+        "use `N` to add or edit a note on the selected item (notes show as ✎, an empty note removes it).",
+        "use `Enter` to read the selected item's full abstract (`q` or `Esc` to go back).",
     ];
 
     let help_text: Vec<_> = HELP_TEXT
@@ -485,8 +5258,142 @@ fn popup_ui<B: Backend>(f: &mut Frame<B>) {
         .collect();
 
     let paragraph = Paragraph::new(help_text)
-        .block(Block::default().borders(Borders::ALL))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("help — j/k to scroll, q to close"),
+        )
         .alignment(Alignment::Left)
-        .wrap(Wrap { trim: true });
+        .wrap(Wrap { trim: true })
+        .scroll((scroll, 0));
     f.render_widget(paragraph, size);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_nothing_selected(app: &App) {
+        assert_eq!(app.current, None);
+        assert_eq!(app.state.selected(), None);
+    }
+
+    #[test]
+    fn navigating_an_empty_page_selects_nothing() {
+        let mut app = App::new();
+        app.next_by(1);
+        assert_nothing_selected(&app);
+        app.previous_by(1);
+        assert_nothing_selected(&app);
+        app.first_item();
+        assert_nothing_selected(&app);
+        app.last_item();
+        assert_nothing_selected(&app);
+    }
+
+    fn app_with(count: usize, wrap_around: bool) -> App {
+        let mut app = App::new();
+        app.items = (0..count)
+            .map(|i| Response {
+                id: i.to_string(),
+                ..Default::default()
+            })
+            .collect();
+        app.wrap_around = wrap_around;
+        app
+    }
+
+    #[test]
+    fn down_wraps_from_the_last_item() {
+        let mut app = app_with(3, true);
+        app.last_item();
+        app.down(1);
+        assert_eq!(app.current, Some(0));
+    }
+
+    #[test]
+    fn up_wraps_from_the_first_item() {
+        let mut app = app_with(3, true);
+        app.first_item();
+        app.up(1);
+        assert_eq!(app.current, Some(2));
+    }
+
+    #[test]
+    fn both_ends_clamp_without_wrap_around() {
+        let mut app = app_with(3, false);
+        app.last_item();
+        app.down(1);
+        assert_eq!(app.current, Some(2));
+        app.first_item();
+        app.up(1);
+        assert_eq!(app.current, Some(0));
+    }
+
+    #[test]
+    fn wrapping_an_empty_page_selects_nothing() {
+        let mut app = app_with(0, true);
+        app.down(1);
+        assert_nothing_selected(&app);
+        app.up(1);
+        assert_nothing_selected(&app);
+    }
+
+    #[test]
+    fn quoted_terms_stay_together() {
+        assert_eq!(
+            search_query(r#"title:"attention is all" you"#, Scope::All, None),
+            r#"ti:"attention is all" AND all:you"#
+        );
+        assert_eq!(
+            search_query(r#""graph neural" nets"#, Scope::Title, None),
+            r#"ti:"graph neural" AND ti:nets"#
+        );
+    }
+
+    #[test]
+    fn or_between_prefixed_terms() {
+        assert_eq!(
+            search_query("author:hinton OR author:lecun", Scope::All, None),
+            "au:hinton OR au:lecun"
+        );
+        assert_eq!(
+            search_query("(author:hinton OR author:lecun) deep", Scope::All, None),
+            "( au:hinton OR au:lecun ) AND all:deep"
+        );
+    }
+
+    #[test]
+    fn or_within_a_scope() {
+        assert_eq!(
+            search_query("graph OR network", Scope::Title, None),
+            "ti:graph OR ti:network"
+        );
+    }
+
+    #[test]
+    fn or_with_a_date_window() {
+        let dates = "submittedDate:[202301010000 TO 202312312359]";
+        assert_eq!(
+            search_query("graph OR network", Scope::All, Some(dates)),
+            format!("(all:graph OR all:network) AND {}", dates)
+        );
+        assert_eq!(
+            search_query("graph network", Scope::All, Some(dates)),
+            format!("all:graph AND all:network AND {}", dates)
+        );
+    }
+
+    #[test]
+    fn dollar_amounts_are_not_math() {
+        let text = "prices between $5 and $10 million";
+        assert_eq!(prettify_latex(text), text);
+    }
+
+    #[test]
+    fn unknown_commands_keep_their_arguments() {
+        assert_eq!(prettify_latex(r"$\frac{1}{2}$"), r"\frac{1}{2}");
+        assert_eq!(prettify_latex(r"$\hat{x}_i$"), r"\hat{x}ᵢ");
+        assert_eq!(prettify_latex(r"$\alpha^2$ and $x_{n}$"), "α² and xₙ");
+    }
+}